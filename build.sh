@@ -8,13 +8,14 @@
 #   cargo +nightly build
 #   arm-none-eabi-gcc -T linker.ld -o target/kernel.elf -z noexecstack -ffreestanding -O2 -nostdlib target/boot.o target/armv6k-none-eabihf/release/libalean.a
 #   arm-none-eabi-objcopy target/kernel.elf -O binary target/kernel.img
+# (The symbol table steps below are optional, without them backtraces show bare addresses.)
 
 # Shortcircuit if any command fails
 set -e
 
 # Check for required tools
 UNINSTALLED_TOOLS=()
-for tool in cargo arm-none-eabi-gcc arm-none-eabi-objcopy
+for tool in cargo arm-none-eabi-gcc arm-none-eabi-objcopy arm-none-eabi-nm
 do
     if ! command -v $tool &> /dev/null
     then
@@ -29,7 +30,7 @@ if [ ${#UNINSTALLED_TOOLS[@]} -ne 0 ]; then
     done
     
     # Check if arm-none-eabi-gcc or arm-none-eabi-objcopy is missing specifically
-    if [[ " ${UNINSTALLED_TOOLS[@]} " =~ " arm-none-eabi-gcc " ]] || [[ " ${UNINSTALLED_TOOLS[@]} " =~ " arm-none-eabi-objcopy " ]]; then
+    if [[ " ${UNINSTALLED_TOOLS[@]} " =~ " arm-none-eabi-gcc " ]] || [[ " ${UNINSTALLED_TOOLS[@]} " =~ " arm-none-eabi-objcopy " ]] || [[ " ${UNINSTALLED_TOOLS[@]} " =~ " arm-none-eabi-nm " ]]; then
         echo ""
         echo "You can install the Arm GNU Toolchain from https://developer.arm.com/Tools%20and%20Software/GNU%20Toolchain"
         echo "Make sure to add the installation path to your PATH environment variable."
@@ -55,6 +56,22 @@ cargo +nightly build $RELEASE_FLAG
 # Link the kernel and boot files into a single ELF
 arm-none-eabi-gcc -T linker.ld -o target/kernel.elf -z noexecstack -ffreestanding -O2 -nostdlib target/armv6k-none-eabihf/$BUILD_TYPE/libalean.a
 
+# Generate the symbol table used for backtraces (see src/debug/symbols.rs): one "address name" line per function.
+# Generic parameters are dropped to keep it small, consecutive symbols with the same name are merged.
+arm-none-eabi-nm -n -C --defined-only target/kernel.elf \
+    | awk '$2 ~ /^[tTwW]$/ && $3 !~ /^\$/ {
+        name = $3; for (i = 4; i <= NF; i++) name = name " " $i
+        sub(/::<.*/, "", name)
+        if (name != last) printf "%s %s\n", $1, name
+        last = name
+      }' > target/ksyms.txt
+arm-none-eabi-objcopy -I binary -O elf32-littlearm -B arm \
+    --rename-section .data=.ksyms,alloc,load,readonly,data,contents \
+    target/ksyms.txt target/ksyms.o
+
+# Link again with the symbol table. It's placed after .text and .rodata, so no function moves.
+arm-none-eabi-gcc -T linker.ld -o target/kernel.elf -z noexecstack -ffreestanding -O2 -nostdlib target/armv6k-none-eabihf/$BUILD_TYPE/libalean.a target/ksyms.o
+
 # Convert the ELF to a binary image
 arm-none-eabi-objcopy target/kernel.elf -O binary target/kernel.img
 
@@ -0,0 +1,8 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Unit tests for the kernel's hardware independent code, run on the host with `cargo test` in this directory.
+//!
+//! The kernel itself only builds for the Pi, so the modules under test are included by path.
+//! They may only depend on `core`.
+
+#[path = "../../src/time/counter.rs"]
+mod counter;
@@ -0,0 +1,293 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Binary buddy allocator for pages.
+//!
+//! Memory is handed out in blocks of 2^order pages, aligned to their own size.
+//! Every page has a [PageInfo] in an array at the start of the managed memory, free blocks are kept
+//! in per-order doubly linked lists threaded through those entries, so allocating and freeing
+//! only ever walks the orders: O(log n).
+#![allow(unused, reason = "These are allocator internals, not all of them are used")]
+
+use core::mem::{align_of, size_of};
+
+pub const PAGE_SIZE: usize = 4096;
+
+/// Largest block is 2^MAX_ORDER pages (128 MiB).
+pub const MAX_ORDER: usize = 15;
+
+/// End of list marker for page indices.
+pub(in crate::alloc) const NONE: u32 = u32::MAX;
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(in crate::alloc) enum PageState {
+  /// Not the first page of a block, its entry means nothing
+  Tail = 0,
+  /// First page of a free block of `order` pages
+  Free = 1,
+  /// First page of an allocated block of `order` pages
+  Allocated = 2,
+  /// Single page carved into objects by the slab allocator
+  Slab = 3,
+}
+
+/// Per page metadata.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(in crate::alloc) struct PageInfo {
+  /// Next page in whichever list this page is on, or [NONE]
+  pub next: u32,
+  /// Previous page in whichever list this page is on, or [NONE]
+  pub prev: u32,
+  pub state: PageState,
+  pub order: u8,
+  /// Size class, for [PageState::Slab] pages
+  pub class: u8,
+  _reserved: u8,
+  /// Objects handed out, for [PageState::Slab] pages
+  pub in_use: u16,
+  /// Offset of the first free object, for [PageState::Slab] pages
+  pub free_offset: u16,
+}
+
+const _: () = assert!(size_of::<PageInfo>() == 16, "PageInfo should stay small, there's one for every page");
+
+impl PageInfo {
+  const EMPTY: Self = Self {
+    next: NONE,
+    prev: NONE,
+    state: PageState::Tail,
+    order: 0,
+    class: 0,
+    _reserved: 0,
+    in_use: 0,
+    free_offset: 0,
+  };
+}
+
+/// Intrusive doubly linked list of pages, linked through [PageInfo::next] and [PageInfo::prev].
+#[derive(Clone, Copy)]
+pub(in crate::alloc) struct PageList {
+  head: u32,
+}
+
+impl PageList {
+  pub const fn new() -> Self {
+    Self { head: NONE }
+  }
+
+  #[inline]
+  pub fn head(&self) -> Option<u32> {
+    (self.head != NONE).then_some(self.head)
+  }
+
+  pub fn push(&mut self, pages: &mut [PageInfo], index: u32) {
+    pages[index as usize].prev = NONE;
+    pages[index as usize].next = self.head;
+    if self.head != NONE {
+      pages[self.head as usize].prev = index;
+    }
+    self.head = index;
+  }
+
+  /// `index` must be on this list.
+  pub fn remove(&mut self, pages: &mut [PageInfo], index: u32) {
+    let PageInfo { next, prev, .. } = pages[index as usize];
+    if prev != NONE {
+      pages[prev as usize].next = next;
+    } else {
+      self.head = next;
+    }
+    if next != NONE {
+      pages[next as usize].prev = prev;
+    }
+    pages[index as usize].next = NONE;
+    pages[index as usize].prev = NONE;
+  }
+}
+
+/// The smallest order whose blocks fit `size` bytes aligned to `align`.
+pub fn order_for(size: usize, align: usize) -> Option<usize> {
+  let pages = size.max(align).div_ceil(PAGE_SIZE).max(1);
+  let order = pages.checked_next_power_of_two()?.trailing_zeros() as usize;
+  (order <= MAX_ORDER).then_some(order)
+}
+
+pub(in crate::alloc) struct PageAllocator {
+  pages: &'static mut [PageInfo],
+  /// Address of the first managed page
+  base: usize,
+  free_lists: [PageList; MAX_ORDER + 1],
+  free_pages: usize,
+}
+
+impl PageAllocator {
+  /// Takes over the memory between `start` and `end`, the [PageInfo] array is placed at `start`
+  /// and the pages after it are managed. Returns `None` if there's not even a single page to manage.
+  ///
+  /// SAFETY: Caller must ensure the memory is RAM that nothing else uses, for as long as the allocator exists.
+  pub unsafe fn new(start: usize, end: usize) -> Option<Self> {
+    let info_start = start.checked_next_multiple_of(align_of::<PageInfo>())?;
+    if end <= info_start {
+      return None;
+    }
+    // Every managed page costs its own size plus its PageInfo.
+    let count = (end - info_start) / (PAGE_SIZE + size_of::<PageInfo>());
+    let base = (info_start + count * size_of::<PageInfo>()).next_multiple_of(PAGE_SIZE);
+    // Aligning the base may have pushed the last page past the end.
+    let count = count.min(end.saturating_sub(base) / PAGE_SIZE).min(NONE as usize);
+    if count == 0 {
+      return None;
+    }
+
+    let infos = info_start as *mut PageInfo;
+    for i in 0..count {
+      // SAFETY: The caller gave us this memory, and the array ends before `base`.
+      unsafe { infos.add(i).write(PageInfo::EMPTY) };
+    }
+
+    let mut allocator = Self {
+      // SAFETY: Just initialized all `count` entries.
+      pages: unsafe { core::slice::from_raw_parts_mut(infos, count) },
+      base,
+      free_lists: [PageList::new(); MAX_ORDER + 1],
+      free_pages: 0,
+    };
+
+    // Carve the pages into the largest blocks that are aligned to their size.
+    let base_pfn = base / PAGE_SIZE;
+    let mut index = 0;
+    while index < count {
+      let mut order = ((base_pfn + index).trailing_zeros() as usize).min(MAX_ORDER);
+      while index + (1 << order) > count {
+        order -= 1;
+      }
+      allocator.push_free(index as u32, order);
+      index += 1 << order;
+    }
+
+    Some(allocator)
+  }
+
+  /// Address of the first managed page.
+  #[inline]
+  pub fn base(&self) -> usize {
+    self.base
+  }
+
+  /// Address just past the last managed page.
+  #[inline]
+  pub fn end(&self) -> usize {
+    self.base + self.pages.len() * PAGE_SIZE
+  }
+
+  #[inline]
+  pub fn total_pages(&self) -> usize {
+    self.pages.len()
+  }
+
+  #[inline]
+  pub fn free_pages(&self) -> usize {
+    self.free_pages
+  }
+
+  #[inline]
+  pub fn infos(&mut self) -> &mut [PageInfo] {
+    self.pages
+  }
+
+  #[inline]
+  pub fn index_of(&self, address: usize) -> Option<u32> {
+    if address < self.base || address >= self.end() {
+      return None;
+    }
+    Some(((address - self.base) / PAGE_SIZE) as u32)
+  }
+
+  #[inline]
+  pub fn address_of(&self, index: u32) -> usize {
+    self.base + index as usize * PAGE_SIZE
+  }
+
+  /// Metadata of the page containing `address`.
+  pub fn info(&self, address: usize) -> Option<PageInfo> {
+    Some(self.pages[self.index_of(address)? as usize])
+  }
+
+  fn push_free(&mut self, index: u32, order: usize) {
+    let page = &mut self.pages[index as usize];
+    page.state = PageState::Free;
+    page.order = order as u8;
+    self.free_lists[order].push(self.pages, index);
+    self.free_pages += 1 << order;
+  }
+
+  fn remove_free(&mut self, index: u32, order: usize) {
+    self.free_lists[order].remove(self.pages, index);
+    self.pages[index as usize].state = PageState::Tail;
+    self.free_pages -= 1 << order;
+  }
+
+  /// Allocates a block of 2^order pages, returning its address.
+  pub fn alloc(&mut self, order: usize) -> Option<usize> {
+    let mut current = (order..=MAX_ORDER).find(|&k| self.free_lists[k].head().is_some())?;
+    let index = self.free_lists[current].head()?;
+    self.remove_free(index, current);
+
+    // Return the upper halves until the block is the requested size.
+    while current > order {
+      current -= 1;
+      self.push_free(index + (1 << current), current);
+    }
+
+    let page = &mut self.pages[index as usize];
+    page.state = PageState::Allocated;
+    page.order = order as u8;
+    Some(self.address_of(index))
+  }
+
+  /// Frees the block starting at `address`, merging it with its free buddies.
+  /// The size of the block is taken from its metadata.
+  pub fn free(&mut self, address: usize) {
+    let Some(mut index) = self.index_of(address) else {
+      return;
+    };
+    let page = self.pages[index as usize];
+    debug_assert!(matches!(page.state, PageState::Allocated | PageState::Slab), "Freeing a block that isn't allocated");
+    if !matches!(page.state, PageState::Allocated | PageState::Slab) {
+      return;
+    }
+
+    self.pages[index as usize].state = PageState::Tail;
+
+    let base_pfn = self.base / PAGE_SIZE;
+    let mut order = page.order as usize;
+    while order < MAX_ORDER {
+      let buddy_pfn = (base_pfn + index as usize) ^ (1 << order);
+      let Some(buddy) = buddy_pfn.checked_sub(base_pfn).filter(|&buddy| buddy < self.pages.len()) else {
+        break;
+      };
+      let buddy_page = self.pages[buddy];
+      if buddy_page.state != PageState::Free || buddy_page.order as usize != order {
+        break;
+      }
+      self.remove_free(buddy as u32, order);
+      index = index.min(buddy as u32);
+      order += 1;
+    }
+    self.push_free(index, order);
+  }
+
+  /// Shrinks the allocated block at `address` to 2^order pages, freeing the rest of it.
+  pub fn shrink(&mut self, address: usize, order: usize) {
+    let Some(index) = self.index_of(address) else {
+      return;
+    };
+    let mut current = self.pages[index as usize].order as usize;
+    // The freed upper halves can't merge, their buddies are the lower halves we keep.
+    while current > order {
+      current -= 1;
+      self.push_free(index + (1 << current), current);
+    }
+    self.pages[index as usize].order = order as u8;
+  }
+}
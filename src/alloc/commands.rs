@@ -0,0 +1,41 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell commands reporting on memory and the heap.
+
+use super::allocator::heap_stats;
+use crate::memory::{self, MemorySource};
+use crate::shell::{Args, Command, CommandResult};
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "meminfo",
+    summary: "prints the memory map and heap usage",
+    help: "",
+    args: &[],
+    run: meminfo,
+  },
+];
+
+fn meminfo(_: &Args) -> CommandResult {
+  let map = memory::memory_map();
+  println!("Memory map (from {}):", match map.source() {
+    MemorySource::Atags => "ATAGS",
+    MemorySource::Mailbox => "the firmware",
+    MemorySource::Fallback => "defaults",
+  });
+  for region in map.regions() {
+    println!("  {:#010x}-{:#010x} {:>8} KiB  {}", region.start, region.end, region.size() / 1024, region.kind.name());
+  }
+  println!("Usable:      {} KiB", map.usable_bytes() / 1024);
+  print_heap();
+  Ok(())
+}
+
+/// Prints a line with the heap usage, also part of `sysinfo`.
+pub fn print_heap() {
+  let stats = heap_stats();
+  println!(
+    "Heap:        {} of {} KiB used at {:#010x}-{:#010x}, {} small allocations in {} pages",
+    (stats.total_bytes() - stats.free_bytes()) / 1024, stats.total_bytes() / 1024, stats.start, stats.end,
+    stats.slab_objects, stats.slab_pages,
+  );
+}
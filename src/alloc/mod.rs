@@ -1,2 +1,4 @@
 pub mod allocator;
-pub(in crate::alloc) mod arbitrary_ptr;
+pub mod commands;
+pub(in crate::alloc) mod buddy;
+pub(in crate::alloc) mod slab;
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Size-class slabs for small allocations.
+//!
+//! Each size class (powers of two from [MIN_OBJECT_SIZE] to [MAX_OBJECT_SIZE]) carves single pages
+//! from the [PageAllocator] into equally sized objects. Free objects form a list inside the page,
+//! linked by their offsets, and pages with free objects are kept on a per-class list, so both
+//! allocating and freeing an object are O(1).
+#![allow(unused, reason = "These are allocator internals, not all of them are used")]
+
+use super::buddy::{PageAllocator, PageList, PageState, PAGE_SIZE};
+
+pub const MIN_OBJECT_SIZE: usize = 16;
+pub const MAX_OBJECT_SIZE: usize = 2048;
+
+const CLASS_COUNT: usize = (MAX_OBJECT_SIZE.trailing_zeros() - MIN_OBJECT_SIZE.trailing_zeros()) as usize + 1;
+
+/// [super::buddy::PageInfo::free_offset] of a page with no free objects.
+const NO_FREE_OBJECT: u16 = u16::MAX;
+
+// Sanity checks. Compile-time assertions, doesn't create any extra runtime code.
+const _: () = assert!(MIN_OBJECT_SIZE.is_power_of_two() && MAX_OBJECT_SIZE.is_power_of_two(), "Object sizes must be powers of two");
+const _: () = assert!(MIN_OBJECT_SIZE >= size_of::<u16>(), "Free objects must fit the offset of the next free object");
+const _: () = assert!(MAX_OBJECT_SIZE < PAGE_SIZE, "A slab must hold more than one object");
+const _: () = assert!(PAGE_SIZE <= NO_FREE_OBJECT as usize, "Object offsets must not collide with NO_FREE_OBJECT");
+
+/// The size class for `size` bytes aligned to `align`, if it's small enough for a slab.
+/// Objects are aligned to their size, so the alignment is covered by rounding up.
+pub fn class_for(size: usize, align: usize) -> Option<u8> {
+  let size = size.max(align).max(MIN_OBJECT_SIZE).checked_next_power_of_two()?;
+  if size > MAX_OBJECT_SIZE {
+    return None;
+  }
+  Some((size.trailing_zeros() - MIN_OBJECT_SIZE.trailing_zeros()) as u8)
+}
+
+#[inline]
+pub const fn object_size(class: u8) -> usize {
+  MIN_OBJECT_SIZE << class
+}
+
+pub(in crate::alloc) struct SlabAllocator {
+  /// Pages with at least one free object, per class
+  partial: [PageList; CLASS_COUNT],
+  pages: usize,
+  objects: usize,
+}
+
+impl SlabAllocator {
+  pub const fn new() -> Self {
+    Self {
+      partial: [PageList::new(); CLASS_COUNT],
+      pages: 0,
+      objects: 0,
+    }
+  }
+
+  /// Pages currently used for slabs.
+  #[inline]
+  pub fn pages(&self) -> usize {
+    self.pages
+  }
+
+  /// Objects currently handed out.
+  #[inline]
+  pub fn objects(&self) -> usize {
+    self.objects
+  }
+
+  pub fn alloc(&mut self, pages: &mut PageAllocator, class: u8) -> Option<usize> {
+    let index = match self.partial[class as usize].head() {
+      Some(index) => index,
+      None => self.grow(pages, class)?,
+    };
+    let page_address = pages.address_of(index);
+    let infos = pages.infos();
+    let info = &mut infos[index as usize];
+
+    let object = page_address + info.free_offset as usize;
+    // SAFETY: Free objects hold the offset of the next free object, written in [SlabAllocator::grow] or [SlabAllocator::free].
+    info.free_offset = unsafe { (object as *const u16).read() };
+    info.in_use += 1;
+    if info.free_offset == NO_FREE_OBJECT {
+      self.partial[class as usize].remove(infos, index);
+    }
+    self.objects += 1;
+    Some(object)
+  }
+
+  /// Frees an object in a [PageState::Slab] page, returning the page once it's empty.
+  pub fn free(&mut self, pages: &mut PageAllocator, address: usize) {
+    let Some(index) = pages.index_of(address) else {
+      return;
+    };
+    let page_address = pages.address_of(index);
+    let infos = pages.infos();
+    let info = &mut infos[index as usize];
+    debug_assert!(info.state == PageState::Slab && info.in_use > 0);
+
+    let class = info.class as usize;
+    let was_full = info.free_offset == NO_FREE_OBJECT;
+    // SAFETY: The object belongs to the caller until now, it's at least MIN_OBJECT_SIZE bytes and aligned to that.
+    unsafe { (address as *mut u16).write(info.free_offset) };
+    info.free_offset = (address - page_address) as u16;
+    info.in_use -= 1;
+    let empty = info.in_use == 0;
+    self.objects -= 1;
+
+    if empty {
+      if !was_full {
+        self.partial[class].remove(infos, index);
+      }
+      pages.free(page_address);
+      self.pages -= 1;
+    } else if was_full {
+      self.partial[class].push(infos, index);
+    }
+  }
+
+  // Takes a new page for `class`, with all of its objects on the free list.
+  fn grow(&mut self, pages: &mut PageAllocator, class: u8) -> Option<u32> {
+    let page_address = pages.alloc(0)?;
+    let index = pages.index_of(page_address)?;
+    let size = object_size(class);
+
+    for offset in (0..PAGE_SIZE).step_by(size) {
+      let next = if offset + size < PAGE_SIZE { (offset + size) as u16 } else { NO_FREE_OBJECT };
+      // SAFETY: The page was just allocated for us.
+      unsafe { ((page_address + offset) as *mut u16).write(next) };
+    }
+
+    let infos = pages.infos();
+    let info = &mut infos[index as usize];
+    info.state = PageState::Slab;
+    info.class = class;
+    info.in_use = 0;
+    info.free_offset = 0;
+    self.partial[class as usize].push(infos, index);
+    self.pages += 1;
+    Some(index)
+  }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell commands reporting on the board and the system.
+
+use core::fmt;
+
+use super::BoardRevision;
+use crate::peripheral::drivers::mailbox::{self, constants::Clock};
+use crate::peripheral::drivers::{timer, watchdog};
+use crate::shell::{ArgKind, ArgSpec, Args, Command, CommandResult};
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "sysinfo",
+    summary: "prints information about the board and the system",
+    help: "Board model, serial number, memory split, clock rates, temperature, uptime, last reset reason and heap usage.",
+    args: &[],
+    run: sysinfo,
+  },
+  Command {
+    name: "uname",
+    summary: "prints the kernel name, or everything about the kernel and board",
+    help: "",
+    args: &[ArgSpec::optional("all", ArgKind::Choice(&["-a"]), "also print the version, architecture and board model")],
+    run: uname,
+  },
+  Command {
+    name: "uptime",
+    summary: "prints the time since boot",
+    help: "",
+    args: &[],
+    run: uptime,
+  },
+];
+
+/// Formats microseconds since boot as days, hours, minutes and seconds.
+struct Uptime(u64);
+
+impl fmt::Display for Uptime {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let seconds = self.0 / 1_000_000;
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+      write!(f, "{} day{}, ", days, if days == 1 { "" } else { "s" })?;
+    }
+    write!(f, "{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds % 60, self.0 / 1000 % 1000)
+  }
+}
+
+fn uname(args: &Args) -> CommandResult {
+  if args.word("all").is_none() {
+    println!("ALEAN");
+    return Ok(());
+  }
+  let model = mailbox::board_revision().map_or("unknown board", |code| BoardRevision::decode(code).model);
+  println!("ALEAN {} armv6 {}", env!("CARGO_PKG_VERSION"), model);
+  Ok(())
+}
+
+fn uptime(_: &Args) -> CommandResult {
+  println!("Up {}", Uptime(timer::timer_counter()));
+  Ok(())
+}
+
+fn sysinfo(_: &Args) -> CommandResult {
+  println!("Kernel:      ALEAN {}", env!("CARGO_PKG_VERSION"));
+  match mailbox::board_revision() {
+    Ok(code) => {
+      let board = BoardRevision::decode(code);
+      println!(
+        "Board:       {} rev {}.{} ({}, {} MiB, made by {}, revision code {:#08x}{})",
+        board.model, board.revision.0, board.revision.1, board.processor, board.memory_mib, board.manufacturer, code,
+        if board.warranty_void { ", warranty void" } else { "" },
+      );
+    }
+    Err(error) => println!("Board:       unavailable ({:?})", error),
+  }
+  match mailbox::board_serial() {
+    Ok(serial) => println!("Serial:      {:016x}", serial),
+    Err(error) => println!("Serial:      unavailable ({:?})", error),
+  }
+  match mailbox::firmware_revision() {
+    Ok(revision) => println!("Firmware:    {:#010x}", revision),
+    Err(error) => println!("Firmware:    unavailable ({:?})", error),
+  }
+  match (mailbox::arm_memory(), mailbox::vc_memory()) {
+    (Ok((arm_base, arm_size)), Ok((vc_base, vc_size))) => println!(
+      "Memory:      ARM {} MiB at {:#010x}, GPU {} MiB at {:#010x}",
+      arm_size >> 20, arm_base, vc_size >> 20, vc_base,
+    ),
+    (Err(error), _) | (_, Err(error)) => println!("Memory:      unavailable ({:?})", error),
+  }
+
+  print!("Clocks:     ");
+  for (name, clock) in [("ARM", Clock::Arm), ("core", Clock::Core), ("SDRAM", Clock::Sdram), ("EMMC", Clock::Emmc), ("UART", Clock::Uart)] {
+    match mailbox::clock_rate(clock) {
+      Ok(rate) => print!(" {} {} MHz", name, rate / 1_000_000),
+      Err(_) => print!(" {} unavailable", name),
+    }
+    if clock == Clock::Arm && let Ok(max) = mailbox::max_clock_rate(clock) {
+      print!(" (max {} MHz)", max / 1_000_000);
+    }
+    if clock != Clock::Uart {
+      print!(",");
+    }
+  }
+  println!();
+
+  match mailbox::temperature() {
+    Ok(temperature) => {
+      print!("Temperature: {}.{} C", temperature / 1000, temperature / 100 % 10);
+      match mailbox::max_temperature() {
+        Ok(max) => println!(" (throttles at {}.{} C)", max / 1000, max / 100 % 10),
+        Err(_) => println!(),
+      }
+    }
+    Err(error) => println!("Temperature: unavailable ({:?})", error),
+  }
+  println!("Uptime:      {}", Uptime(timer::timer_counter()));
+  println!("Last reset:  {}", watchdog::reset_reason().name());
+  crate::alloc::commands::print_heap();
+  Ok(())
+}
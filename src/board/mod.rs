@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Board identification from the revision code the firmware reports.
+//!
+//! See https://www.raspberrypi.com/documentation/computers/raspberry-pi.html#raspberry-pi-revision-codes
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+pub mod commands;
+
+/// A decoded board revision code.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoardRevision {
+  /// The raw revision code
+  pub code: u32,
+  pub model: &'static str,
+  pub processor: &'static str,
+  pub manufacturer: &'static str,
+  /// Board revision, e.g. `(1, 2)` for 1.2
+  pub revision: (u8, u8),
+  /// Memory on the board in MiB
+  pub memory_mib: u32,
+  /// Set if the board has been overvolted (old-style codes), or had its warranty voided that way (new-style codes)
+  pub warranty_void: bool,
+}
+
+// These are for reference only, to avoid magic numbers in the code.
+mod bits {
+  /// Set for new-style revision codes
+  pub const NEW_STYLE: u32 = 23;
+  /// Old-style codes: set if the board was overvolted
+  pub const OLD_WARRANTY: u32 = 24;
+  /// New-style codes: set if the board was overvolted
+  pub const NEW_WARRANTY: u32 = 25;
+
+  pub const MEMORY_SHIFT: u32 = 20;
+  pub const MEMORY_MASK: u32 = 0b111;
+  pub const MANUFACTURER_SHIFT: u32 = 16;
+  pub const MANUFACTURER_MASK: u32 = 0xF;
+  pub const PROCESSOR_SHIFT: u32 = 12;
+  pub const PROCESSOR_MASK: u32 = 0xF;
+  pub const TYPE_SHIFT: u32 = 4;
+  pub const TYPE_MASK: u32 = 0xFF;
+  pub const REVISION_MASK: u32 = 0xF;
+
+  /// Old-style codes: the code itself, without the warranty bit
+  pub const OLD_CODE_MASK: u32 = 0x00FF_FFFF;
+}
+
+const UNKNOWN: &str = "unknown";
+
+const PROCESSORS: [&str; 5] = ["BCM2835", "BCM2836", "BCM2837", "BCM2711", "BCM2712"];
+
+const MANUFACTURERS: [&str; 6] = ["Sony UK", "Egoman", "Embest", "Sony Japan", "Embest", "Stadium"];
+
+/// Board types of new-style codes, indexed by the type field.
+const MODELS: [&str; 0x1B] = [
+  "Raspberry Pi Model A",
+  "Raspberry Pi Model B",
+  "Raspberry Pi Model A+",
+  "Raspberry Pi Model B+",
+  "Raspberry Pi 2 Model B",
+  "Raspberry Pi Alpha",
+  "Raspberry Pi Compute Module 1",
+  UNKNOWN,
+  "Raspberry Pi 3 Model B",
+  "Raspberry Pi Zero",
+  "Raspberry Pi Compute Module 3",
+  UNKNOWN,
+  "Raspberry Pi Zero W",
+  "Raspberry Pi 3 Model B+",
+  "Raspberry Pi 3 Model A+",
+  UNKNOWN,
+  "Raspberry Pi Compute Module 3+",
+  "Raspberry Pi 4 Model B",
+  "Raspberry Pi Zero 2 W",
+  "Raspberry Pi 400",
+  "Raspberry Pi Compute Module 4",
+  "Raspberry Pi Compute Module 4S",
+  UNKNOWN,
+  "Raspberry Pi 5",
+  "Raspberry Pi Compute Module 5",
+  "Raspberry Pi 500",
+  "Raspberry Pi Compute Module 5 Lite",
+];
+
+/// A board with an old-style code (first generation boards), these don't have any fields to decode.
+struct OldStyleBoard {
+  code: u32,
+  model: &'static str,
+  revision: (u8, u8),
+  memory_mib: u32,
+  manufacturer: &'static str,
+}
+
+const OLD_STYLE_BOARDS: [OldStyleBoard; 17] = [
+  OldStyleBoard { code: 0x0002, model: "Raspberry Pi Model B", revision: (1, 0), memory_mib: 256, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x0003, model: "Raspberry Pi Model B", revision: (1, 0), memory_mib: 256, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x0004, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 256, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x0005, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 256, manufacturer: "Qisda" },
+  OldStyleBoard { code: 0x0006, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 256, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x0007, model: "Raspberry Pi Model A", revision: (2, 0), memory_mib: 256, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x0008, model: "Raspberry Pi Model A", revision: (2, 0), memory_mib: 256, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x0009, model: "Raspberry Pi Model A", revision: (2, 0), memory_mib: 256, manufacturer: "Qisda" },
+  OldStyleBoard { code: 0x000D, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 512, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x000E, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 512, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x000F, model: "Raspberry Pi Model B", revision: (2, 0), memory_mib: 512, manufacturer: "Egoman" },
+  OldStyleBoard { code: 0x0010, model: "Raspberry Pi Model B+", revision: (1, 2), memory_mib: 512, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x0011, model: "Raspberry Pi Compute Module 1", revision: (1, 0), memory_mib: 512, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x0012, model: "Raspberry Pi Model A+", revision: (1, 1), memory_mib: 256, manufacturer: "Sony UK" },
+  OldStyleBoard { code: 0x0013, model: "Raspberry Pi Model B+", revision: (1, 2), memory_mib: 512, manufacturer: "Embest" },
+  OldStyleBoard { code: 0x0014, model: "Raspberry Pi Compute Module 1", revision: (1, 0), memory_mib: 512, manufacturer: "Embest" },
+  OldStyleBoard { code: 0x0015, model: "Raspberry Pi Model A+", revision: (1, 1), memory_mib: 256, manufacturer: "Embest" },
+];
+
+impl BoardRevision {
+  /// Decodes a revision code, as returned by [crate::peripheral::drivers::mailbox::board_revision].
+  pub fn decode(code: u32) -> Self {
+    if code & (1 << bits::NEW_STYLE) != 0 {
+      return Self::decode_new_style(code);
+    }
+
+    let board = OLD_STYLE_BOARDS.iter().find(|board| board.code == code & bits::OLD_CODE_MASK);
+    Self {
+      code,
+      model: board.map_or(UNKNOWN, |board| board.model),
+      // Every board with an old-style code has a BCM2835.
+      processor: PROCESSORS[0],
+      manufacturer: board.map_or(UNKNOWN, |board| board.manufacturer),
+      revision: board.map_or((0, 0), |board| board.revision),
+      memory_mib: board.map_or(0, |board| board.memory_mib),
+      warranty_void: code & (1 << bits::OLD_WARRANTY) != 0,
+    }
+  }
+
+  fn decode_new_style(code: u32) -> Self {
+    let field = |shift: u32, mask: u32| ((code >> shift) & mask) as usize;
+    let lookup = |table: &[&'static str], index: usize| table.get(index).copied().unwrap_or(UNKNOWN);
+    Self {
+      code,
+      model: lookup(&MODELS, field(bits::TYPE_SHIFT, bits::TYPE_MASK)),
+      processor: lookup(&PROCESSORS, field(bits::PROCESSOR_SHIFT, bits::PROCESSOR_MASK)),
+      manufacturer: lookup(&MANUFACTURERS, field(bits::MANUFACTURER_SHIFT, bits::MANUFACTURER_MASK)),
+      // New-style codes only have the minor revision, the major one is always 1.
+      revision: (1, field(0, bits::REVISION_MASK) as u8),
+      memory_mib: 256 << field(bits::MEMORY_SHIFT, bits::MEMORY_MASK),
+      warranty_void: code & (1 << bits::NEW_WARRANTY) != 0,
+    }
+  }
+}
@@ -16,8 +16,33 @@
 // preserve these registers as argument for kernel_main
 
 _start:
-  // Setup the stack.
+  // Setup the stacks. Every exception mode has its own banked stack pointer,
+  // see linker.ld for their locations. Interrupts stay masked in all of them.
+  cpsid if, #0x11 // FIQ
+  ldr sp, =__fiq_stack_top
+  cpsid if, #0x12 // IRQ
+  ldr sp, =__irq_stack_top
+  cpsid if, #0x17 // Abort
+  ldr sp, =__abt_stack_top
+  cpsid if, #0x1B // Undefined
+  ldr sp, =__und_stack_top
+  // The kernel itself runs in supervisor mode.
+  cpsid if, #0x13 // Supervisor
   mov sp, #0x8000
+
+  // Use low exception vectors (clear the V bit in the control register).
+  mrc p15, 0, r3, c1, c0, 0
+  bic r3, r3, #(1 << 13)
+  mcr p15, 0, r3, c1, c0, 0
+
+  // Install the exception vector table (8 vectors + 8 handler addresses) at 0x0000.
+  // See exception/vectors.s
+  ldr r3, =__exception_vectors
+  mov r4, #0
+  ldmia r3!, {r5-r12}
+  stmia r4!, {r5-r12}
+  ldmia r3!, {r5-r12}
+  stmia r4!, {r5-r12}
  
   // Clear out bss.
   ldr r4, =__bss_start
@@ -37,7 +62,8 @@ _start:
   cmp r4, r9
   blo 1b
  
-  // Call kernel_main
+  // Call kernel_main, with a null frame pointer to terminate backtraces.
+  mov r11, #0
   ldr r3, =kernel_main
   blx r3
  
@@ -0,0 +1,157 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Kernel console, a [core::fmt::Write] sink over the selected serial port.
+//! Output is also passed to any [ConsoleSink]s, e.g. the framebuffer console.
+//!
+//! Use the crate-wide [print!] and [println!] macros to write to it.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+use crate::peripheral::serial::SerialPort;
+
+// Index of the console's [SerialPort], see [port_to_index].
+static PORT: AtomicU8 = AtomicU8::new(0);
+// Set once a port has been brought up, writing to an unconfigured UART may never complete.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Receives everything written to the console, next to the serial port.
+/// Sinks get the output as it was written, `\n` is not translated for them.
+pub type ConsoleSink = fn(&[u8]);
+
+const MAX_SINKS: usize = 4;
+// Registered sinks as function addresses, 0 being an empty slot.
+static SINKS: [AtomicUsize; MAX_SINKS] = [const { AtomicUsize::new(0) }; MAX_SINKS];
+
+fn port_to_index(port: SerialPort) -> u8 {
+  match port {
+    SerialPort::Uart0 => 0,
+    SerialPort::MiniUart => 1,
+  }
+}
+
+fn index_to_port(index: u8) -> SerialPort {
+  match index {
+    1 => SerialPort::MiniUart,
+    _ => SerialPort::Uart0,
+  }
+}
+
+/// Brings up `port` and makes it the console.
+pub fn init(port: SerialPort) {
+  port.init();
+  PORT.store(port_to_index(port), Ordering::Relaxed);
+  INITIALIZED.store(true, Ordering::Release);
+}
+
+/// Whether a serial port has been set up as the console yet.
+pub fn is_initialized() -> bool {
+  INITIALIZED.load(Ordering::Acquire)
+}
+
+/// Switches the console to another serial port, flushing pending output on the current one first.
+pub fn set_port(port: SerialPort) {
+  let current = self::port();
+  if current == port {
+    return;
+  }
+  current.flush();
+  init(port);
+}
+
+/// The serial port the console is currently on.
+pub fn port() -> SerialPort {
+  index_to_port(PORT.load(Ordering::Relaxed))
+}
+
+/// Adds `sink` to the console outputs. Returns `false` if there's no room for another one.
+pub fn add_sink(sink: ConsoleSink) -> bool {
+  if SINKS.iter().any(|slot| slot.load(Ordering::Acquire) == sink as usize) {
+    return true;
+  }
+  SINKS.iter().any(|slot| slot.compare_exchange(0, sink as usize, Ordering::AcqRel, Ordering::Relaxed).is_ok())
+}
+
+/// Removes `sink` from the console outputs, if it was added.
+pub fn remove_sink(sink: ConsoleSink) {
+  for slot in &SINKS {
+    let _ = slot.compare_exchange(sink as usize, 0, Ordering::AcqRel, Ordering::Relaxed);
+  }
+}
+
+fn write_sinks(bytes: &[u8]) {
+  for slot in &SINKS {
+    let address = slot.load(Ordering::Acquire);
+    if address != 0 {
+      // SAFETY: Non-zero slots only ever hold a ConsoleSink, stored by add_sink.
+      let sink = unsafe { core::mem::transmute::<usize, ConsoleSink>(address) };
+      sink(bytes);
+    }
+  }
+}
+
+/// Reads a byte from the console, waiting until one arrives.
+pub fn read_byte() -> u8 {
+  port().read_blocking()
+}
+
+/// Reads a byte from the console if there is one, without blocking.
+pub fn try_read_byte() -> Option<u8> {
+  port().try_read()
+}
+
+/// Writes raw bytes to the console, without any newline translation.
+pub fn write_bytes(bytes: &[u8]) {
+  let port = port();
+  for &b in bytes {
+    port.write_byte(b);
+  }
+  write_sinks(bytes);
+}
+
+/// Waits until all pending console output has been sent.
+pub fn flush() {
+  port().flush();
+}
+
+/// Writes to the console, translating `\n` into `\r\n` for terminals.
+pub struct Console;
+
+impl Write for Console {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let port = port();
+    for &b in s.as_bytes() {
+      if b == b'\n' {
+        port.write_byte(b'\r');
+      }
+      port.write_byte(b);
+    }
+    write_sinks(s.as_bytes());
+    Ok(())
+  }
+}
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+  // Console never fails to write.
+  let _ = Console.write_fmt(args);
+}
+
+/// Prints to the kernel console.
+#[macro_export]
+macro_rules! print {
+  ($($arg:tt)*) => {
+    $crate::console::_print(format_args!($($arg)*))
+  };
+}
+
+/// Prints to the kernel console, with a newline.
+#[macro_export]
+macro_rules! println {
+  () => {
+    $crate::print!("\n")
+  };
+  ($($arg:tt)*) => {
+    $crate::console::_print(format_args!("{}\n", format_args!($($arg)*)))
+  };
+}
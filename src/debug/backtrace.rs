@@ -0,0 +1,93 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Frame pointer based stack walking.
+//!
+//! The target spec forces frame pointers, so every function pushes a frame record of
+//! `{r11, lr}` and points r11 at it: `[r11]` is the caller's frame pointer, `[r11 + 4]` the return address.
+//! `_start` calls `kernel_main` with r11 = 0, which ends the chain.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt::{self, Write};
+
+use super::symbols;
+
+/// Frames deeper than this are not walked, in case the chain is corrupted into a loop.
+pub const MAX_DEPTH: usize = 32;
+
+/// Frame records are only followed if they're below this address, anything above is MMIO.
+const MEMORY_END: u32 = 0x2000_0000;
+
+/// Iterator over the return addresses on the stack, starting from the frame record `fp` points to.
+pub struct Backtrace {
+  fp: u32,
+  depth: usize,
+}
+
+impl Backtrace {
+  /// Walks the frame chain starting at the frame pointer `fp` (r11).
+  pub fn from_frame_pointer(fp: u32) -> Self {
+    Self { fp, depth: 0 }
+  }
+
+  /// Walks the frame chain of the caller.
+  #[inline(always)]
+  pub fn current() -> Self {
+    let fp: u32;
+    // SAFETY: Reading r11 has no side effects.
+    unsafe { core::arch::asm!("mov {}, r11", out(reg) fp, options(nomem, nostack, preserves_flags)) };
+    Self::from_frame_pointer(fp)
+  }
+}
+
+impl Iterator for Backtrace {
+  type Item = u32;
+
+  fn next(&mut self) -> Option<u32> {
+    let fp = self.fp;
+    if fp == 0 || !fp.is_multiple_of(4) || fp >= MEMORY_END - 8 || self.depth >= MAX_DEPTH {
+      return None;
+    }
+    // SAFETY: `fp` is an aligned address in RAM, the worst a bogus frame pointer can do is produce a bogus address.
+    let (next_fp, return_address) = unsafe {
+      (core::ptr::read_volatile(fp as *const u32), core::ptr::read_volatile((fp + 4) as *const u32))
+    };
+    // Stacks grow down, so the caller's frame must be above this one. Anything else means the chain is broken.
+    self.fp = if next_fp > fp { next_fp } else { 0 };
+    self.depth += 1;
+    if return_address == 0 {
+      self.fp = 0;
+      return None;
+    }
+    Some(return_address)
+  }
+}
+
+/// Writes `address` along with the function it belongs to, if the symbol table has it.
+pub fn write_address(out: &mut impl Write, address: u32) -> fmt::Result {
+  write_symbolized(out, address, address)
+}
+
+// `containing` is the address used to find the function, `address` is the one printed.
+fn write_symbolized(out: &mut impl Write, address: u32, containing: u32) -> fmt::Result {
+  match symbols::lookup(containing) {
+    Some(symbol) => write!(out, "{:#010x}  {}+{:#x}", address, symbol.name, address - symbol.address),
+    None => write!(out, "{:#010x}  ???", address),
+  }
+}
+
+/// Writes a backtrace, starting at `pc` and continuing with the frame chain at `fp`.
+pub fn write_backtrace(out: &mut impl Write, pc: u32, fp: u32) -> fmt::Result {
+  writeln!(out, "Backtrace:")?;
+  write!(out, "  #0  ")?;
+  write_address(out, pc)?;
+  writeln!(out)?;
+  for (i, return_address) in Backtrace::from_frame_pointer(fp).enumerate() {
+    write!(out, "  #{:<2} ", i + 1)?;
+    // Resolve the call instruction rather than the return address, which may already be in the next function.
+    write_symbolized(out, return_address, return_address.wrapping_sub(4))?;
+    writeln!(out)?;
+  }
+  if !symbols::is_available() {
+    writeln!(out, "  (no symbol table, build with build.sh to resolve addresses)")?;
+  }
+  Ok(())
+}
@@ -0,0 +1,5 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Debugging aids: stack backtraces and the embedded kernel symbol table.
+
+pub mod backtrace;
+pub mod symbols;
@@ -0,0 +1,66 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Lookup of function names in the embedded symbol table.
+//!
+//! The table is generated by `build.sh` from the linked kernel and linked into the `.ksyms` section.
+//! It is plain text, one `<8 hex digit address> <demangled name>\n` line per function, sorted by address.
+//! Builds that don't go through `build.sh` have an empty table, and addresses are left unresolved.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+unsafe extern "C" {
+  // SAFETY: linker provides these symbols
+  static __ksyms_start: u8;
+  static __ksyms_end: u8;
+  static __text_end: u8;
+}
+
+/// A function in the symbol table.
+#[derive(Clone, Copy, Debug)]
+pub struct Symbol {
+  pub address: u32,
+  pub name: &'static str,
+}
+
+fn table() -> &'static [u8] {
+  // SAFETY: The linker places the symbol table between these two symbols, and it is never written.
+  unsafe {
+    let start = &raw const __ksyms_start;
+    let end = &raw const __ksyms_end;
+    core::slice::from_raw_parts(start, end as usize - start as usize)
+  }
+}
+
+/// Whether a symbol table was linked into the kernel.
+pub fn is_available() -> bool {
+  !table().is_empty()
+}
+
+fn parse_address(hex: &[u8]) -> Option<u32> {
+  let mut value: u32 = 0;
+  for &c in hex {
+    let digit = (c as char).to_digit(16)?;
+    value = (value << 4) | digit;
+  }
+  Some(value)
+}
+
+// Lines that don't parse are skipped, a truncated table only loses the symbols it's missing.
+fn symbols() -> impl Iterator<Item = Symbol> {
+  table().split(|&b| b == b'\n').filter_map(|line| {
+    if line.len() < 10 || line[8] != b' ' {
+      return None;
+    }
+    Some(Symbol {
+      address: parse_address(&line[..8])?,
+      name: core::str::from_utf8(&line[9..]).ok()?,
+    })
+  })
+}
+
+/// Finds the function containing `address`, i.e. the closest symbol at or below it.
+pub fn lookup(address: u32) -> Option<Symbol> {
+  let text_end = (&raw const __text_end) as u32;
+  if address >= text_end {
+    return None;
+  }
+  symbols().take_while(|symbol| symbol.address <= address).last()
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! ARM exception handling.
+//!
+//! The vector table lives in `vectors.s` and is copied to 0x0000 by `_start`.
+//! Every exception (apart from reset, which restarts the kernel from `_start`)
+//! saves an [ExceptionContext] on the stack and calls [exception_dispatch],
+//! which passes the context to the handler registered for that exception.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::cell::UnsafeCell;
+use core::fmt;
+
+use crate::util::cpu;
+
+core::arch::global_asm!(include_str!("vectors.s"), options(raw));
+
+/// The exceptions that can be handled by registering an [ExceptionHandler].
+/// The discriminants must match the `KIND_*` constants in `vectors.s`.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExceptionKind {
+  Undefined = 0,
+  SoftwareInterrupt = 1,
+  PrefetchAbort = 2,
+  DataAbort = 3,
+  Irq = 4,
+  Fiq = 5,
+}
+
+const EXCEPTION_KIND_COUNT: usize = 6;
+
+impl ExceptionKind {
+  #[inline]
+  fn from_index(index: u32) -> Option<Self> {
+    match index {
+      0 => Some(ExceptionKind::Undefined),
+      1 => Some(ExceptionKind::SoftwareInterrupt),
+      2 => Some(ExceptionKind::PrefetchAbort),
+      3 => Some(ExceptionKind::DataAbort),
+      4 => Some(ExceptionKind::Irq),
+      5 => Some(ExceptionKind::Fiq),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match *self {
+      ExceptionKind::Undefined => "undefined instruction",
+      ExceptionKind::SoftwareInterrupt => "software interrupt",
+      ExceptionKind::PrefetchAbort => "prefetch abort",
+      ExceptionKind::DataAbort => "data abort",
+      ExceptionKind::Irq => "IRQ",
+      ExceptionKind::Fiq => "FIQ",
+    }
+  }
+}
+
+/// Register state of the interrupted code, saved on exception entry.
+/// The layout must match the frame built by `exception_common` in `vectors.s`.
+///
+/// Changes made to `r`, `pc` and `cpsr` by a handler are restored when it returns. `lr` is only restored if the
+/// exception is handled in the mode it interrupted (e.g. an IRQ taken in SVC mode), otherwise the interrupted mode's
+/// banked link register is left as is. `sp` is informational only.
+///
+/// Note: FIQs are handled in FIQ mode, so `r[8..=12]` holds the FIQ banked registers.
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct ExceptionContext {
+  /// Stack pointer of the interrupted mode
+  pub sp: u32,
+  /// Link register of the interrupted mode
+  pub lr: u32,
+  /// General purpose registers r0-r12
+  pub r: [u32; 13],
+  _padding: u32,
+  /// Address execution resumes at.
+  /// For undefined instructions and aborts, this is the address of the faulting instruction.
+  pub pc: u32,
+  /// CPSR of the interrupted code
+  pub cpsr: u32,
+}
+
+const _: () = assert!(core::mem::size_of::<ExceptionContext>() == 72, "ExceptionContext must match the frame built in vectors.s");
+
+impl ExceptionContext {
+  /// Snapshot of the current registers, for reporting where no exception frame is available.
+  /// `pc` is the address of the capturing code, the general purpose registers hold whatever the caller left in them.
+  #[inline(always)]
+  pub fn capture() -> Self {
+    let mut context = ExceptionContext {
+      sp: 0,
+      lr: 0,
+      r: [0; 13],
+      _padding: 0,
+      pc: 0,
+      cpsr: 0,
+    };
+    // SAFETY: Only stores into `context.r`, which has room for all 13 registers.
+    unsafe {
+      core::arch::asm!(
+        "stmia {regs}, {{r0-r12}}",
+        regs = in(reg) context.r.as_mut_ptr(),
+        options(nostack, preserves_flags),
+      );
+      core::arch::asm!(
+        "mov {sp}, sp",
+        "mov {lr}, lr",
+        "mov {pc}, pc",
+        "mrs {cpsr}, cpsr",
+        sp = out(reg) context.sp,
+        lr = out(reg) context.lr,
+        pc = out(reg) context.pc,
+        cpsr = out(reg) context.cpsr,
+        options(nomem, nostack, preserves_flags),
+      );
+    }
+    context
+  }
+
+  /// The comment field of the `svc` instruction that caused a software interrupt.
+  /// Only meaningful for [ExceptionKind::SoftwareInterrupt].
+  pub fn svc_number(&self) -> u32 {
+    // SAFETY: The instruction before the return address is the `svc` instruction that was just executed.
+    let instruction = unsafe { core::ptr::read_volatile((self.pc - 4) as *const u32) };
+    instruction & 0x00FF_FFFF
+  }
+}
+
+impl fmt::Display for ExceptionContext {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, value) in self.r.iter().enumerate() {
+      write!(f, "{:>4}: {:#010x}", ["r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12"][i], value)?;
+      if i % 4 == 3 {
+        writeln!(f)?;
+      } else {
+        write!(f, "  ")?;
+      }
+    }
+    writeln!(f, "  sp: {:#010x}    lr: {:#010x}    pc: {:#010x}", self.sp, self.lr, self.pc)?;
+    write!(
+      f,
+      "cpsr: {:#010x} (mode {}, irq {}, fiq {})",
+      self.cpsr,
+      cpu::mode_name(self.cpsr),
+      if self.cpsr & (1 << 7) != 0 { "masked" } else { "enabled" },
+      if self.cpsr & (1 << 6) != 0 { "masked" } else { "enabled" },
+    )
+  }
+}
+
+pub type ExceptionHandler = fn(&mut ExceptionContext);
+
+struct HandlerTable(UnsafeCell<[Option<ExceptionHandler>; EXCEPTION_KIND_COUNT]>);
+
+// SAFETY: The table is only written with IRQs and FIQs masked, and this is a single core system.
+unsafe impl Sync for HandlerTable {}
+
+static HANDLERS: HandlerTable = HandlerTable(UnsafeCell::new([None; EXCEPTION_KIND_COUNT]));
+
+/// Register a handler for an exception, replacing the previous one.
+pub fn register_handler(kind: ExceptionKind, handler: ExceptionHandler) {
+  set_handler(kind, Some(handler));
+}
+
+/// Remove the handler for an exception.
+/// Unhandled exceptions panic, reporting the saved context.
+pub fn unregister_handler(kind: ExceptionKind) {
+  set_handler(kind, None);
+}
+
+fn set_handler(kind: ExceptionKind, handler: Option<ExceptionHandler>) {
+  cpu::without_interrupts(|| {
+    // SAFETY: IRQs and FIQs are masked, so no exception handler can be looking at the table.
+    unsafe { (*HANDLERS.0.get())[kind as usize] = handler };
+  });
+}
+
+fn handler(kind: ExceptionKind) -> Option<ExceptionHandler> {
+  // SAFETY: Writes only happen with interrupts masked, so this read can't observe a partial write.
+  unsafe { (*HANDLERS.0.get())[kind as usize] }
+}
+
+/// Called from `exception_common` in `vectors.s` with the saved context of the interrupted code.
+#[unsafe(no_mangle)]
+extern "C" fn exception_dispatch(kind: u32, context: &mut ExceptionContext) {
+  let Some(kind) = ExceptionKind::from_index(kind) else {
+    panic!("Unknown exception {}", kind);
+  };
+
+  match handler(kind) {
+    Some(handler) => handler(context),
+    None => unhandled_exception(kind, context),
+  }
+}
+
+fn unhandled_exception(kind: ExceptionKind, context: &ExceptionContext) -> ! {
+  crate::panic::set_exception_context(kind, context);
+  match kind {
+    ExceptionKind::DataAbort => panic!(
+      "Unhandled {} exception at {:#010x}, accessing {:#010x} (status {:#05x})",
+      kind.name(),
+      context.pc,
+      cpu::data_fault_address(),
+      cpu::data_fault_status() & 0x40F,
+    ),
+    ExceptionKind::PrefetchAbort => panic!(
+      "Unhandled {} exception at {:#010x} (status {:#05x})",
+      kind.name(),
+      context.pc,
+      cpu::instruction_fault_status() & 0x40F,
+    ),
+    _ => panic!("Unhandled {} exception at {:#010x}", kind.name(), context.pc),
+  }
+}
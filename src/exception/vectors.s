@@ -0,0 +1,135 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+// This file is included in exception/mod.rs via global_asm!
+
+.section ".text"
+
+// Processor mode numbers, as found in the CPSR M[4:0] field.
+.equ MODE_USR, 0x10
+.equ MODE_FIQ, 0x11
+.equ MODE_SVC, 0x13
+.equ MODE_ABT, 0x17
+.equ MODE_UND, 0x1B
+.equ MODE_SYS, 0x1F
+
+// Exception kinds, must match ExceptionKind in exception/mod.rs
+.equ KIND_UNDEFINED, 0
+.equ KIND_SOFTWARE_INTERRUPT, 1
+.equ KIND_PREFETCH_ABORT, 2
+.equ KIND_DATA_ABORT, 3
+.equ KIND_IRQ, 4
+.equ KIND_FIQ, 5
+
+// Offset of the saved CPSR from the saved r0, see ExceptionContext.
+.equ FRAME_CPSR_FROM_R0, 60
+// Size of the frame from the saved r0 to the end, see ExceptionContext.
+.equ FRAME_SIZE_FROM_R0, 64
+
+// Exception vector table, copied to 0x0000 by _start.
+// Each entry loads its handler address from the word 32 bytes after it,
+// so the table keeps working after being copied.
+.balign 32
+.globl __exception_vectors
+__exception_vectors:
+  ldr pc, [pc, #24] // 0x00 Reset
+  ldr pc, [pc, #24] // 0x04 Undefined instruction
+  ldr pc, [pc, #24] // 0x08 Software interrupt
+  ldr pc, [pc, #24] // 0x0C Prefetch abort
+  ldr pc, [pc, #24] // 0x10 Data abort
+  ldr pc, [pc, #24] // 0x14 Reserved
+  ldr pc, [pc, #24] // 0x18 IRQ
+  ldr pc, [pc, #24] // 0x1C FIQ
+  .word _start
+  .word exception_undefined
+  .word exception_software_interrupt
+  .word exception_prefetch_abort
+  .word exception_data_abort
+  .word exception_reserved
+  .word exception_irq
+  .word exception_fiq
+
+// Builds the first part of an ExceptionContext on the stack of `mode`.
+// `lr_offset` adjusts the link register so that it holds the address to return to.
+.macro exception_entry kind, lr_offset, mode
+  .if \lr_offset
+  sub lr, lr, #\lr_offset
+  .endif
+  // Store the return address and SPSR on the stack of `mode`, then switch to it.
+  srsdb sp!, #\mode
+  cps #\mode
+  // Padding to keep the frame a multiple of 8 bytes.
+  sub sp, sp, #4
+  stmfd sp!, {r0-r12}
+  mov r0, #\mode
+  mov r1, #\kind
+  b exception_common
+.endm
+
+exception_undefined:
+  exception_entry KIND_UNDEFINED, 4, MODE_UND
+
+exception_software_interrupt:
+  exception_entry KIND_SOFTWARE_INTERRUPT, 0, MODE_SVC
+
+exception_prefetch_abort:
+  exception_entry KIND_PREFETCH_ABORT, 4, MODE_ABT
+
+exception_data_abort:
+  exception_entry KIND_DATA_ABORT, 8, MODE_ABT
+
+// IRQs are handled on the supervisor stack, so that the interrupted kernel code
+// and the IRQ handler share a stack and handlers may switch between them.
+exception_irq:
+  exception_entry KIND_IRQ, 4, MODE_SVC
+
+exception_fiq:
+  exception_entry KIND_FIQ, 4, MODE_FIQ
+
+// The reserved vector is never taken on the ARM1176.
+exception_reserved:
+  b exception_reserved
+
+// r0 -> mode the exception is being handled in
+// r1 -> exception kind
+// sp -> saved r0 of a partially built ExceptionContext
+exception_common:
+  // Fetch the banked sp and lr of the interrupted mode.
+  ldr r2, [sp, #FRAME_CPSR_FROM_R0]
+  and r2, r2, #0x1F
+  // User mode shares its registers with System mode, which we can switch back out of.
+  cmp r2, #MODE_USR
+  moveq r2, #MODE_SYS
+  cmp r2, r0
+  beq 1f
+  mrs r3, cpsr
+  bic r4, r3, #0x1F
+  orr r4, r4, r2
+  msr cpsr_c, r4
+  mov r5, sp
+  mov r6, lr
+  msr cpsr_c, r3
+  b 2f
+1:
+  // The exception is handled in the interrupted mode,
+  // its stack pointer is just above the frame and its link register is still live.
+  add r5, sp, #FRAME_SIZE_FROM_R0
+  mov r6, lr
+2:
+  stmfd sp!, {r5, r6}
+
+  // exception_dispatch(kind, &mut ExceptionContext)
+  mov r0, r1
+  mov r1, sp
+  // Keep the frame address in a callee-saved register, and align the stack for AAPCS.
+  mov r4, sp
+  bic sp, sp, #7
+  bl exception_dispatch
+  mov sp, r4
+
+  // The banked stack pointer of the interrupted mode is left as is. The saved lr goes back to this mode's lr,
+  // which is only the interrupted mode's if the exception is handled in the mode it interrupted.
+  add sp, sp, #4
+  ldmfd sp!, {lr}
+  ldmfd sp!, {r0-r12}
+  add sp, sp, #4
+  // Return to the saved pc, restoring the saved CPSR.
+  rfeia sp!
@@ -3,47 +3,82 @@
 #![no_std]
 #![feature(likely_unlikely)]
 
+// The kernel's own allocator is [crate::alloc], so the alloc crate goes by another name.
+extern crate alloc as rust_alloc;
+
+#[macro_use]
+mod console;
+#[macro_use]
+mod log;
+
 mod alloc;
+mod board;
+mod debug;
+mod exception;
+mod memory;
+mod panic;
 mod peripheral;
 mod util;
+mod video;
 mod shell;
+mod sync;
+mod thread;
+mod time;
 
-use peripheral::drivers::gpio;
-use peripheral::drivers::gpio::constants::PinFunction;
-use peripheral::drivers::timer::util::wait_nanos;
-
-use crate::peripheral::drivers::{uart::{uart_set_fifo, uart_write_str}, watchdog};
+use crate::peripheral::drivers::{interrupt, mailbox, watchdog};
+use crate::peripheral::serial::SerialPort;
+use crate::util::cpu;
 
 core::arch::global_asm!(include_str!("boot.s"), options(raw));
 
+/// Serial port used as the console at boot.
+/// QEMU connects its first serial port to the PL011, boards with wireless (e.g. the Pi Zero W)
+/// have the mini UART on the GPIO header instead. Can be changed at runtime with the `console` shell command.
+const CONSOLE_PORT: SerialPort = SerialPort::Uart0;
+
+/// Called by `_start` in `boot.s`, with the registers the firmware started the kernel with.
 #[unsafe(no_mangle)]
-pub extern "C" fn kernel_main() -> ! {
-  uart_set_fifo(true);
-  uart_write_str("No kernel implementation yet\n");
-  shell::shell_main();
-  uart_write_str("Shutting down.\n");
+pub extern "C" fn kernel_main(_r0: u32, machine_id: u32, atags: u32) -> ! {
+  // SAFETY: This is the first thing the kernel does, nothing has allocated yet.
+  let memory_map = unsafe { memory::init(atags) };
 
-  watchdog::power_off();
-}
+  interrupt::init();
+  time::timers::init();
+  thread::init();
+  console::init(CONSOLE_PORT);
+  mailbox::mailbox_enable_interrupts();
+  cpu::irq_enable();
 
-const ACT_LED: u32 = 47;
-
-// TODO: Log panic info, disable core feature `panic_immediate_abort`
-#[panic_handler]
-pub fn kernel_panic(_info: &core::panic::PanicInfo) -> ! {
-  // Spin loop
-
-  // Set pin 47 to output
-  gpio::pin_function_set(ACT_LED, PinFunction::OUTPUT);
-
-  loop {
-    // Set pin to HIGH
-    gpio::pin_output_set(ACT_LED);
-    // Wait 1 second
-    wait_nanos(1_000_000_000);
-    // Set pin to LOW
-    gpio::pin_output_clear(ACT_LED);
-    // Wait 1 second
-    wait_nanos(1_000_000_000);
+  info!("ALEAN {} booting, machine id {:#x}", env!("CARGO_PKG_VERSION"), machine_id);
+  match memory_map.source() {
+    memory::MemorySource::Atags => {}
+    memory::MemorySource::Mailbox => debug!("No ATAGS at {:#010x}, memory size taken from the firmware", atags),
+    memory::MemorySource::Fallback => warn!(
+      "No ATAGS at {:#010x}{}, assuming RAM up to {:#010x}",
+      atags,
+      if memory::atags::is_device_tree(atags) { " (got a device tree, set device_tree= in config.txt)" } else { "" },
+      memory::FALLBACK_MEMORY_END,
+    ),
+  }
+  info!("Memory: {} KiB usable", memory_map.usable_bytes() / 1024);
+  for region in memory_map.regions() {
+    debug!("  {:#010x}-{:#010x} {}", region.start, region.end, region.kind.name());
+  }
+  match video::init_console() {
+    Ok((framebuffer, columns, rows)) => info!(
+      "Framebuffer: {}x{} at {:#010x}, {}x{} text console",
+      framebuffer.width, framebuffer.height, framebuffer.address, columns, rows,
+    ),
+    Err(error) => warn!("No framebuffer console: {:?}", error),
   }
+  shell::register(board::commands::COMMANDS);
+  shell::register(alloc::commands::COMMANDS);
+  shell::register(memory::commands::COMMANDS);
+  shell::register(thread::commands::COMMANDS);
+  shell::register(watchdog::commands::COMMANDS);
+  shell::shell_main();
+  println!("Shutting down.");
+  console::flush();
+
+  watchdog::power_off();
 }
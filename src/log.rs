@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Leveled kernel logging to the console.
+//!
+//! Messages are prefixed with the uptime from the system timer and their level,
+//! and are dropped if their level is above the runtime filter (see [set_level]).
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::peripheral::drivers::timer::timer_counter;
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LogLevel {
+  Error = 1,
+  Warn = 2,
+  Info = 3,
+  Debug = 4,
+  Trace = 5,
+}
+
+impl LogLevel {
+  pub fn name(&self) -> &'static str {
+    match *self {
+      LogLevel::Error => "error",
+      LogLevel::Warn => "warn",
+      LogLevel::Info => "info",
+      LogLevel::Debug => "debug",
+      LogLevel::Trace => "trace",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "error" => Some(LogLevel::Error),
+      "warn" => Some(LogLevel::Warn),
+      "info" => Some(LogLevel::Info),
+      "debug" => Some(LogLevel::Debug),
+      "trace" => Some(LogLevel::Trace),
+      _ => None,
+    }
+  }
+
+  fn from_value(value: u8) -> Self {
+    match value {
+      1 => LogLevel::Error,
+      2 => LogLevel::Warn,
+      3 => LogLevel::Info,
+      4 => LogLevel::Debug,
+      _ => LogLevel::Trace,
+    }
+  }
+
+  // Fixed width label, so messages line up.
+  fn label(&self) -> &'static str {
+    match *self {
+      LogLevel::Error => "ERROR",
+      LogLevel::Warn => "WARN ",
+      LogLevel::Info => "INFO ",
+      LogLevel::Debug => "DEBUG",
+      LogLevel::Trace => "TRACE",
+    }
+  }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+/// Only messages at `level` or more severe are printed.
+pub fn set_level(level: LogLevel) {
+  LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> LogLevel {
+  LogLevel::from_value(LEVEL.load(Ordering::Relaxed))
+}
+
+#[inline]
+pub fn enabled(level: LogLevel) -> bool {
+  level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+#[doc(hidden)]
+pub fn _log(level: LogLevel, args: fmt::Arguments) {
+  if !enabled(level) {
+    return;
+  }
+  let micros = timer_counter();
+  crate::println!("[{:5}.{:06}] {} {}", micros / 1_000_000, micros % 1_000_000, level.label(), args);
+}
+
+/// Logs a message at [LogLevel::Error].
+#[macro_export]
+macro_rules! error {
+  ($($arg:tt)*) => {
+    $crate::log::_log($crate::log::LogLevel::Error, format_args!($($arg)*))
+  };
+}
+
+/// Logs a message at [LogLevel::Warn].
+#[macro_export]
+macro_rules! warn {
+  ($($arg:tt)*) => {
+    $crate::log::_log($crate::log::LogLevel::Warn, format_args!($($arg)*))
+  };
+}
+
+/// Logs a message at [LogLevel::Info].
+#[macro_export]
+macro_rules! info {
+  ($($arg:tt)*) => {
+    $crate::log::_log($crate::log::LogLevel::Info, format_args!($($arg)*))
+  };
+}
+
+/// Logs a message at [LogLevel::Debug].
+#[macro_export]
+macro_rules! debug {
+  ($($arg:tt)*) => {
+    $crate::log::_log($crate::log::LogLevel::Debug, format_args!($($arg)*))
+  };
+}
+
+/// Logs a message at [LogLevel::Trace].
+#[macro_export]
+macro_rules! trace {
+  ($($arg:tt)*) => {
+    $crate::log::_log($crate::log::LogLevel::Trace, format_args!($($arg)*))
+  };
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! ARM boot tags (ATAGS), passed by the firmware in r2.
+//!
+//! The list is a sequence of tags, each starting with a header of its size in words and its type,
+//! beginning with [ATAG_CORE] and ending with [ATAG_NONE].
+//! Note: if the firmware boots with a device tree, r2 points to that instead, see [is_device_tree].
+#![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
+
+pub const ATAG_NONE: u32 = 0x0000_0000;
+pub const ATAG_CORE: u32 = 0x5441_0001;
+pub const ATAG_MEM: u32 = 0x5441_0002;
+pub const ATAG_VIDEOTEXT: u32 = 0x5441_0003;
+pub const ATAG_RAMDISK: u32 = 0x5441_0004;
+pub const ATAG_INITRD2: u32 = 0x5442_0005;
+pub const ATAG_SERIAL: u32 = 0x5441_0006;
+pub const ATAG_REVISION: u32 = 0x5441_0007;
+pub const ATAG_VIDEOLFB: u32 = 0x5441_0008;
+pub const ATAG_CMDLINE: u32 = 0x5441_0009;
+
+/// Magic number at the start of a flattened device tree (stored big endian).
+const FDT_MAGIC: u32 = 0xD00D_FEED;
+
+/// Tags are only looked for below this address, anything above is MMIO.
+const MEMORY_END: u32 = 0x2000_0000;
+
+/// Tags larger than this (in words) are treated as corruption.
+const MAX_TAG_WORDS: u32 = 0x1000;
+
+/// A single boot tag.
+#[derive(Clone, Copy, Debug)]
+pub enum Tag {
+  Core { flags: u32, page_size: u32, root_device: u32 },
+  /// A bank of RAM available to the ARM
+  Memory { start: u32, size: u32 },
+  /// Kernel command line (`cmdline.txt`)
+  CommandLine(&'static str),
+  /// Any other tag, with its data (without the header)
+  Other { tag: u32, data: &'static [u32] },
+}
+
+/// Whether `address` holds a flattened device tree instead of an ATAG list.
+pub fn is_device_tree(address: u32) -> bool {
+  if address == 0 || !address.is_multiple_of(4) || address >= MEMORY_END {
+    return false;
+  }
+  // SAFETY: An aligned address in RAM, reading it has no side effects.
+  let magic = unsafe { core::ptr::read_volatile(address as *const u32) };
+  u32::from_be(magic) == FDT_MAGIC
+}
+
+/// A validated ATAG list.
+#[derive(Clone, Copy, Debug)]
+pub struct Atags {
+  start: u32,
+}
+
+impl Atags {
+  /// Checks that `address` points to an ATAG list, i.e. starts with an [ATAG_CORE] tag.
+  pub fn from_address(address: u32) -> Option<Self> {
+    if address == 0 || !address.is_multiple_of(4) || address >= MEMORY_END - 8 {
+      return None;
+    }
+    let (size, tag) = read_header(address);
+    // The core tag may be empty (2 words), or carry flags, page size and root device (5 words).
+    if tag != ATAG_CORE || (size != 2 && size != 5) {
+      return None;
+    }
+    Some(Self { start: address })
+  }
+
+  pub fn start(&self) -> u32 {
+    self.start
+  }
+
+  /// Address just past the terminating [ATAG_NONE] tag.
+  pub fn end(&self) -> u32 {
+    let mut iter = self.iter();
+    while iter.next().is_some() {}
+    // ATAG_NONE is a 2 word header with no data.
+    iter.address + 8
+  }
+
+  pub fn iter(&self) -> AtagIter {
+    AtagIter { address: self.start }
+  }
+
+  /// The kernel command line, if the firmware passed one.
+  pub fn command_line(&self) -> Option<&'static str> {
+    self.iter().find_map(|tag| match tag {
+      Tag::CommandLine(cmdline) => Some(cmdline),
+      _ => None,
+    })
+  }
+}
+
+fn read_header(address: u32) -> (u32, u32) {
+  // SAFETY: Callers check the address is aligned and in RAM.
+  unsafe {
+    (core::ptr::read_volatile(address as *const u32), core::ptr::read_volatile((address + 4) as *const u32))
+  }
+}
+
+/// Iterator over the tags of an [Atags] list, ending at [ATAG_NONE] or the first malformed tag.
+pub struct AtagIter {
+  address: u32,
+}
+
+impl Iterator for AtagIter {
+  type Item = Tag;
+
+  fn next(&mut self) -> Option<Tag> {
+    if self.address >= MEMORY_END - 8 {
+      return None;
+    }
+    let (size, tag) = read_header(self.address);
+    if tag == ATAG_NONE || !(2..=MAX_TAG_WORDS).contains(&size) || self.address + size * 4 > MEMORY_END {
+      return None;
+    }
+
+    let data_address = self.address + 8;
+    // SAFETY: The tag lies in RAM (checked above), and the firmware doesn't touch it after boot.
+    let data = unsafe { core::slice::from_raw_parts(data_address as *const u32, (size - 2) as usize) };
+    self.address += size * 4;
+
+    Some(match tag {
+      ATAG_CORE if data.len() >= 3 => Tag::Core { flags: data[0], page_size: data[1], root_device: data[2] },
+      ATAG_MEM if data.len() >= 2 => Tag::Memory { size: data[0], start: data[1] },
+      ATAG_CMDLINE => {
+        // SAFETY: The command line is a NUL terminated string filling the tag data.
+        let bytes = unsafe { core::slice::from_raw_parts(data_address as *const u8, data.len() * 4) };
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        Tag::CommandLine(core::str::from_utf8(&bytes[..len]).unwrap_or(""))
+      }
+      _ => Tag::Other { tag, data },
+    })
+  }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell commands for reading and writing memory and peripheral registers.
+
+use core::ptr::{read_volatile, write_volatile};
+
+use rust_alloc::format;
+
+use super::{memory_map, MMIO_START};
+use crate::peripheral::drivers::mailbox;
+use crate::peripheral::registers::{self, PAGE_SIZE};
+use crate::shell::{self, ArgKind, ArgSpec, Args, Command, CommandError, CommandResult};
+
+const ADDRESS_HELP: &str = "\
+Addresses are physical (0x20201018), peripheral bus addresses (0x7E201018) or register names (UART_FR).
+Only RAM and peripherals with a driver can be accessed, and peripherals only 32 bits at a time.";
+
+const WIDTH: ArgSpec = ArgSpec::optional("width", ArgKind::Choice(&["8", "16", "32"]), "bits accessed at a time");
+
+/// End of the peripheral address range.
+const MMIO_END: u32 = 0x2100_0000;
+/// Bytes per line of `hexdump`.
+const HEXDUMP_LINE: u32 = 16;
+/// Differences `memcmp` prints before only counting them.
+const MAX_DIFFERENCES: u32 = 16;
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "hexdump",
+    summary: "prints memory in hex and ASCII",
+    help: ADDRESS_HELP,
+    args: &[
+      ArgSpec::required("address", ArgKind::Address, "where to start"),
+      ArgSpec::required("length", ArgKind::Integer, "bytes to print"),
+      WIDTH,
+    ],
+    run: hexdump,
+  },
+  Command {
+    name: "memcmp",
+    summary: "compares two blocks of memory",
+    help: ADDRESS_HELP,
+    args: &[
+      ArgSpec::required("a", ArgKind::Address, "first block"),
+      ArgSpec::required("b", ArgKind::Address, "second block"),
+      ArgSpec::required("length", ArgKind::Integer, "bytes to compare"),
+      WIDTH,
+    ],
+    run: memcmp,
+  },
+  Command {
+    name: "memset",
+    summary: "fills memory with a value",
+    help: ADDRESS_HELP,
+    args: &[
+      ArgSpec::required("address", ArgKind::Address, "where to start"),
+      ArgSpec::required("value", ArgKind::Integer, "value to write"),
+      ArgSpec::required("length", ArgKind::Integer, "bytes to fill"),
+      WIDTH,
+    ],
+    run: memset,
+  },
+  Command {
+    name: "peek",
+    summary: "reads memory or a register",
+    help: ADDRESS_HELP,
+    args: &[ArgSpec::required("address", ArgKind::Address, "what to read"), WIDTH],
+    run: peek,
+  },
+  Command {
+    name: "poke",
+    summary: "writes memory or a register",
+    help: ADDRESS_HELP,
+    args: &[
+      ArgSpec::required("address", ArgKind::Address, "what to write"),
+      ArgSpec::required("value", ArgKind::Integer, "value to write"),
+      WIDTH,
+    ],
+    run: poke,
+  },
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Width {
+  Byte,
+  Half,
+  Word,
+}
+
+impl Width {
+  fn from_args(args: &Args, default: Width) -> Self {
+    match args.word("width") {
+      Some("8") => Width::Byte,
+      Some("16") => Width::Half,
+      Some("32") => Width::Word,
+      _ => default,
+    }
+  }
+
+  fn bytes(self) -> u32 {
+    match self {
+      Width::Byte => 1,
+      Width::Half => 2,
+      Width::Word => 4,
+    }
+  }
+
+  /// Hex digits needed for a value of this width.
+  fn digits(self) -> usize {
+    self.bytes() as usize * 2
+  }
+
+  fn fits(self, value: u32) -> bool {
+    self == Width::Word || value >> (self.bytes() * 8) == 0
+  }
+}
+
+/// Where RAM ends, including the GPU's part of it.
+fn ram_end() -> u32 {
+  match mailbox::vc_memory() {
+    Ok((base, size)) => base.saturating_add(size).min(MMIO_START as u32),
+    Err(_) => memory_map().regions().last().map_or(0, |region| region.end as u32),
+  }
+}
+
+/// Checks that the `length` bytes at `address` are mapped and can be accessed `width` at a time.
+fn check(address: u32, length: u32, width: Width) -> CommandResult {
+  let failed = |message| Err(CommandError::Failed(message));
+  if !address.is_multiple_of(width.bytes()) || !length.is_multiple_of(width.bytes()) {
+    return failed(format!("The address and length must be multiples of {} for {} bit accesses", width.bytes(), width.bytes() * 8));
+  }
+  let Some(end) = address.checked_add(length) else {
+    return failed("The range goes past the end of the address space".into());
+  };
+  if end <= ram_end() {
+    return Ok(());
+  }
+  if address >= MMIO_START as u32 && end <= MMIO_END {
+    if width != Width::Word {
+      return failed("Peripherals can only be accessed 32 bits at a time".into());
+    }
+    let mut page = address - address % PAGE_SIZE;
+    while page < end {
+      if !registers::is_known_peripheral(page) {
+        return failed(format!("{:#010x} doesn't belong to a peripheral with a driver", page.max(address)));
+      }
+      page += PAGE_SIZE;
+    }
+    return Ok(());
+  }
+  failed(format!("{:#010x}-{:#010x} isn't mapped to RAM or a peripheral", address, end))
+}
+
+/// SAFETY: The address must have been checked with [check].
+unsafe fn load(address: u32, width: Width) -> u32 {
+  // SAFETY: The address is mapped and aligned, see the contract of this function.
+  unsafe {
+    match width {
+      Width::Byte => read_volatile(address as *const u8) as u32,
+      Width::Half => read_volatile(address as *const u16) as u32,
+      Width::Word => read_volatile(address as *const u32),
+    }
+  }
+}
+
+/// SAFETY: The address must have been checked with [check], and writing to it must not break anything the kernel relies on.
+unsafe fn store(address: u32, width: Width, value: u32) {
+  // SAFETY: The address is mapped and aligned, see the contract of this function.
+  unsafe {
+    match width {
+      Width::Byte => write_volatile(address as *mut u8, value as u8),
+      Width::Half => write_volatile(address as *mut u16, value as u16),
+      Width::Word => write_volatile(address as *mut u32, value),
+    }
+  }
+}
+
+fn hexdump(args: &Args) -> CommandResult {
+  let address = args.integer("address").unwrap_or_default();
+  let length = args.integer("length").unwrap_or_default();
+  let width = Width::from_args(args, Width::Byte);
+  check(address, length, width)?;
+
+  let end = address + length;
+  let mut line = address;
+  while line < end {
+    if shell::interrupted() {
+      return Err(CommandError::Exit(shell::INTERRUPTED_STATUS));
+    }
+    let line_end = end.min(line + HEXDUMP_LINE);
+    let mut ascii = [b' '; HEXDUMP_LINE as usize];
+    print!("{:#010x} ", line);
+    for offset in (0..HEXDUMP_LINE).step_by(width.bytes() as usize) {
+      if line + offset >= line_end {
+        print!(" {:1$}", "", width.digits());
+        continue;
+      }
+      // SAFETY: Checked above.
+      let value = unsafe { load(line + offset, width) };
+      print!(" {:01$x}", value, width.digits());
+      for (i, byte) in value.to_le_bytes()[..width.bytes() as usize].iter().enumerate() {
+        ascii[(offset as usize) + i] = if byte.is_ascii_graphic() || *byte == b' ' { *byte } else { b'.' };
+      }
+    }
+    // Only printable ASCII ends up in the buffer.
+    println!("  |{}|", core::str::from_utf8(&ascii).unwrap_or(""));
+    line = line_end;
+  }
+  Ok(())
+}
+
+fn memcmp(args: &Args) -> CommandResult {
+  let a = args.integer("a").unwrap_or_default();
+  let b = args.integer("b").unwrap_or_default();
+  let length = args.integer("length").unwrap_or_default();
+  let width = Width::from_args(args, Width::Byte);
+  check(a, length, width)?;
+  check(b, length, width)?;
+
+  let mut differences = 0;
+  for offset in (0..length).step_by(width.bytes() as usize) {
+    // SAFETY: Checked above.
+    let (x, y) = unsafe { (load(a + offset, width), load(b + offset, width)) };
+    if x != y {
+      if differences < MAX_DIFFERENCES {
+        println!("{:#010x}: {:04$x} != {:#010x}: {:04$x}", a + offset, x, b + offset, y, width.digits());
+      }
+      differences += 1;
+    }
+  }
+  if differences == 0 {
+    println!("Identical");
+    return Ok(());
+  }
+  if differences > MAX_DIFFERENCES {
+    println!("... {} differences in total", differences);
+  }
+  Err(CommandError::Exit(1))
+}
+
+fn memset(args: &Args) -> CommandResult {
+  let address = args.integer("address").unwrap_or_default();
+  let value = args.integer("value").unwrap_or_default();
+  let length = args.integer("length").unwrap_or_default();
+  let width = Width::from_args(args, Width::Byte);
+  if !width.fits(value) {
+    return Err(CommandError::Failed(format!("{:#x} doesn't fit in {} bits", value, width.bytes() * 8)));
+  }
+  check(address, length, width)?;
+
+  for offset in (0..length).step_by(width.bytes() as usize) {
+    // SAFETY: Checked above, and the user asked for it.
+    unsafe { store(address + offset, width, value) };
+  }
+  Ok(())
+}
+
+fn peek(args: &Args) -> CommandResult {
+  let address = args.integer("address").unwrap_or_default();
+  let width = Width::from_args(args, Width::Word);
+  check(address, width.bytes(), width)?;
+
+  // SAFETY: Checked above.
+  let value = unsafe { load(address, width) };
+  match registers::at(address) {
+    Some(register) => println!("{:#010x} {}: {:#04$x} ({})", address, register.name, value, value, width.digits() + 2),
+    None => println!("{:#010x}: {:#03$x} ({})", address, value, value, width.digits() + 2),
+  }
+  Ok(())
+}
+
+fn poke(args: &Args) -> CommandResult {
+  let address = args.integer("address").unwrap_or_default();
+  let value = args.integer("value").unwrap_or_default();
+  let width = Width::from_args(args, Width::Word);
+  if !width.fits(value) {
+    return Err(CommandError::Failed(format!("{:#x} doesn't fit in {} bits", value, width.bytes() * 8)));
+  }
+  check(address, width.bytes(), width)?;
+
+  // SAFETY: Checked above, and the user asked for it.
+  unsafe { store(address, width, value) };
+  Ok(())
+}
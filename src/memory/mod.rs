@@ -0,0 +1,246 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Physical memory map.
+//!
+//! Built once at boot from the ATAGS the firmware passes in r2, or by asking the firmware through
+//! the mailbox if there are none. Either way only the ARM's share of RAM is reported,
+//! so memory given to the GPU never shows up as usable.
+//! The kernel image, its stacks, the exception vectors and the ATAGS themselves are reserved,
+//! and the largest usable region is handed to the heap.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::cell::UnsafeCell;
+
+use self::atags::{Atags, Tag};
+use crate::alloc::allocator;
+use crate::peripheral::drivers::mailbox;
+
+pub mod atags;
+pub mod commands;
+
+/// Memory-Mapped I/O (MMIO) region start address for BCM2835.
+/// No RAM is usable at or above this address.
+pub const MMIO_START: usize = 0x2000_0000;
+
+/// End of ARM memory assumed when the firmware doesn't tell us.
+/// This is the default 64 MiB GPU memory split on a 512 MiB board.
+pub const FALLBACK_MEMORY_END: usize = 0x1C00_0000;
+
+/// The supervisor stack grows down from here, see `boot.s`. Below it are the exception vectors.
+const KERNEL_LOAD_ADDRESS: usize = 0x8000;
+
+/// Maximum number of regions in the memory map.
+const MAX_REGIONS: usize = 16;
+
+// Sanity checks. Compile-time assertions, doesn't create any extra runtime code.
+const _: () = assert!(FALLBACK_MEMORY_END <= MMIO_START, "FALLBACK_MEMORY_END must not reach into the MMIO region");
+const _: () = assert!(FALLBACK_MEMORY_END.is_multiple_of(4096), "FALLBACK_MEMORY_END must be page aligned");
+
+unsafe extern "C" {
+  // SAFETY: linker provides these symbols
+  static __start: u8;
+  static __stacks_start: u8;
+  static __stacks_end: u8;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RegionKind {
+  /// Free for the kernel to use
+  Usable,
+  /// Exception vectors and the supervisor stack
+  LowMemory,
+  /// Kernel code, data and bss
+  Kernel,
+  /// Exception mode stacks
+  Stacks,
+  /// Boot tags from the firmware
+  Atags,
+}
+
+impl RegionKind {
+  pub fn name(&self) -> &'static str {
+    match *self {
+      RegionKind::Usable => "usable",
+      RegionKind::LowMemory => "vectors/stack",
+      RegionKind::Kernel => "kernel",
+      RegionKind::Stacks => "stacks",
+      RegionKind::Atags => "atags",
+    }
+  }
+}
+
+/// A range of physical memory, `start` inclusive, `end` exclusive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Region {
+  pub start: usize,
+  pub end: usize,
+  pub kind: RegionKind,
+}
+
+impl Region {
+  #[inline]
+  pub fn size(&self) -> usize {
+    self.end - self.start
+  }
+}
+
+/// Where the memory map came from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemorySource {
+  Atags,
+  /// The firmware's ARM memory property
+  Mailbox,
+  /// Nothing usable was passed, [FALLBACK_MEMORY_END] was assumed
+  Fallback,
+}
+
+/// Sorted, non-overlapping list of memory regions.
+#[derive(Clone, Copy)]
+pub struct MemoryMap {
+  regions: [Region; MAX_REGIONS],
+  len: usize,
+  source: MemorySource,
+}
+
+impl MemoryMap {
+  const fn new() -> Self {
+    Self {
+      regions: [Region { start: 0, end: 0, kind: RegionKind::Usable }; MAX_REGIONS],
+      len: 0,
+      source: MemorySource::Fallback,
+    }
+  }
+
+  pub fn regions(&self) -> &[Region] {
+    &self.regions[..self.len]
+  }
+
+  pub fn source(&self) -> MemorySource {
+    self.source
+  }
+
+  pub fn usable(&self) -> impl Iterator<Item = &Region> {
+    self.regions().iter().filter(|region| region.kind == RegionKind::Usable)
+  }
+
+  pub fn usable_bytes(&self) -> usize {
+    self.usable().map(Region::size).sum()
+  }
+
+  pub fn largest_usable(&self) -> Option<Region> {
+    self.usable().max_by_key(|region| region.size()).copied()
+  }
+
+  fn insert(&mut self, region: Region) -> bool {
+    if region.start >= region.end || self.len == MAX_REGIONS {
+      return false;
+    }
+    let index = self.regions().iter().position(|r| r.start > region.start).unwrap_or(self.len);
+    self.regions.copy_within(index..self.len, index + 1);
+    self.regions[index] = region;
+    self.len += 1;
+    true
+  }
+
+  /// Adds a bank of RAM, clipped to below the MMIO region. Parts overlapping known regions are dropped.
+  fn add_usable(&mut self, start: usize, end: usize) {
+    let end = end.min(MMIO_START);
+    let mut start = start;
+    // Only add the gaps between existing regions.
+    for i in 0..self.len {
+      let existing = self.regions[i];
+      if existing.end <= start {
+        continue;
+      }
+      if existing.start >= end {
+        break;
+      }
+      if existing.start > start {
+        self.insert(Region { start, end: existing.start, kind: RegionKind::Usable });
+      }
+      start = start.max(existing.end);
+    }
+    self.insert(Region { start, end, kind: RegionKind::Usable });
+  }
+
+  /// Marks part of the usable memory as `kind`. Only usable memory is ever taken, memory that is
+  /// already reserved or isn't RAM is left as it is.
+  fn reserve(&mut self, start: usize, end: usize, kind: RegionKind) {
+    let mut i = 0;
+    while i < self.len {
+      let region = self.regions[i];
+      if region.kind != RegionKind::Usable || region.end <= start || region.start >= end {
+        i += 1;
+        continue;
+      }
+      // Split the usable region into the part before, the reserved part and the part after.
+      self.len -= 1;
+      self.regions.copy_within(i + 1..self.len + 1, i);
+      self.insert(Region { start: region.start, end: start.max(region.start), kind: RegionKind::Usable });
+      self.insert(Region { start: start.max(region.start), end: end.min(region.end), kind });
+      self.insert(Region { start: end.min(region.end), end: region.end, kind: RegionKind::Usable });
+      // The pieces are sorted back in place, continue after the reserved part.
+      i = self.regions().iter().position(|r| r.start >= end.min(region.end)).unwrap_or(self.len);
+    }
+  }
+}
+
+struct MemoryMapCell(UnsafeCell<MemoryMap>);
+
+// SAFETY: The map is only written by [init] during boot, before anything else reads it.
+unsafe impl Sync for MemoryMapCell {}
+
+static MEMORY_MAP: MemoryMapCell = MemoryMapCell(UnsafeCell::new(MemoryMap::new()));
+
+/// Builds the memory map from the boot tags at `atags_address` (or the mailbox) and gives the largest usable region to the heap.
+///
+/// SAFETY: Must be called once during boot, before anything allocates or reads the memory map.
+pub unsafe fn init(atags_address: u32) -> &'static MemoryMap {
+  let mut map = MemoryMap::new();
+  let atags = Atags::from_address(atags_address);
+
+  if let Some(atags) = atags {
+    for tag in atags.iter() {
+      if let Tag::Memory { start, size } = tag {
+        map.add_usable(start as usize, start as usize + size as usize);
+      }
+    }
+  }
+  if map.len > 0 {
+    map.source = MemorySource::Atags;
+  } else if let Ok((base, size)) = mailbox::arm_memory() {
+    map.add_usable(base as usize, base as usize + size as usize);
+    map.source = MemorySource::Mailbox;
+  }
+  if map.len == 0 {
+    map.add_usable(0, FALLBACK_MEMORY_END);
+    map.source = MemorySource::Fallback;
+  }
+
+  // Reserve the tags first, they're usually inside the low memory region.
+  if let Some(atags) = atags {
+    map.reserve(atags.start() as usize, atags.end() as usize, RegionKind::Atags);
+  }
+  let (kernel_start, stacks_start, stacks_end) =
+    ((&raw const __start) as usize, (&raw const __stacks_start) as usize, (&raw const __stacks_end) as usize);
+  map.reserve(0, KERNEL_LOAD_ADDRESS.min(kernel_start), RegionKind::LowMemory);
+  map.reserve(kernel_start, stacks_start, RegionKind::Kernel);
+  map.reserve(stacks_start, stacks_end, RegionKind::Stacks);
+
+  // SAFETY: Nothing reads the map before init returns, see the contract of this function.
+  let map = unsafe {
+    *MEMORY_MAP.0.get() = map;
+    &*MEMORY_MAP.0.get()
+  };
+
+  if let Some(heap) = map.largest_usable() {
+    // SAFETY: The region is usable RAM, and nothing has allocated yet.
+    unsafe { allocator::init_heap(heap.start, heap.end) };
+  }
+  map
+}
+
+/// The memory map built by [init].
+pub fn memory_map() -> &'static MemoryMap {
+  // SAFETY: Only written by init during boot, see [MemoryMapCell].
+  unsafe { &*MEMORY_MAP.0.get() }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Kernel panic handler.
+//!
+//! A panic masks interrupts, reports the message, location, uptime, registers and a backtrace on the console
+//! (all output is polled with interrupts masked), optionally arms the watchdog to reboot the board,
+//! and then blinks an error code on the ACT LED forever.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::cell::UnsafeCell;
+use core::fmt::Write;
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::console::{self, Console};
+use crate::debug::backtrace;
+use crate::exception::{ExceptionContext, ExceptionKind};
+use crate::peripheral::drivers::gpio::{self, constants::PinFunction};
+use crate::peripheral::drivers::timer::timer_counter;
+use crate::peripheral::drivers::watchdog;
+use crate::time;
+use crate::util::cpu;
+
+const ACT_LED: u32 = 47;
+
+/// Longest reboot delay the watchdog can count down, in seconds.
+pub const MAX_REBOOT_DELAY_SECS: u32 = 15;
+
+/// Error codes blinked on the ACT LED, as that many short flashes followed by a pause.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlinkCode {
+  /// A panic from kernel code
+  Panic = 1,
+  /// Panicked again while handling a panic
+  DoublePanic = 2,
+  /// An exception nobody handled, the code is `3 + ExceptionKind`
+  Exception = 3,
+}
+
+// Seconds until the watchdog reboots the board after a panic, 0 means halt instead.
+static REBOOT_DELAY_SECS: AtomicU32 = AtomicU32::new(0);
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+struct PanicContext(UnsafeCell<Option<(ExceptionKind, ExceptionContext)>>);
+
+// SAFETY: Only written right before panicking from an exception handler, and only read by the panic handler,
+// which runs with interrupts masked and never returns.
+unsafe impl Sync for PanicContext {}
+
+static EXCEPTION_CONTEXT: PanicContext = PanicContext(UnsafeCell::new(None));
+
+/// Reboot the board through the watchdog `secs` seconds after a panic,
+/// or halt forever if `secs` is 0. Delays are capped at [MAX_REBOOT_DELAY_SECS].
+pub fn set_reboot_delay(secs: u32) {
+  REBOOT_DELAY_SECS.store(secs.min(MAX_REBOOT_DELAY_SECS), Ordering::Relaxed);
+}
+
+pub fn reboot_delay() -> u32 {
+  REBOOT_DELAY_SECS.load(Ordering::Relaxed)
+}
+
+/// Records the saved context of an unhandled exception, so the panic that follows reports
+/// the registers of the faulting code instead of its own.
+pub fn set_exception_context(kind: ExceptionKind, context: &ExceptionContext) {
+  cpu::interrupts_save();
+  // SAFETY: Interrupts are masked, and this is followed by a panic, see [PanicContext].
+  unsafe { *EXCEPTION_CONTEXT.0.get() = Some((kind, context.clone())) };
+}
+
+#[panic_handler]
+fn kernel_panic(info: &PanicInfo) -> ! {
+  let registers = ExceptionContext::capture();
+  cpu::interrupts_save();
+
+  if PANICKING.swap(true, Ordering::Relaxed) {
+    // Reporting the first panic failed, don't try again.
+    blink_forever(BlinkCode::DoublePanic as u32);
+  }
+
+  // SAFETY: Interrupts are masked and this is the only panic, see [PanicContext].
+  let exception = unsafe { (*EXCEPTION_CONTEXT.0.get()).take() };
+  let code = match exception {
+    Some((kind, _)) => BlinkCode::Exception as u32 + kind as u32,
+    None => BlinkCode::Panic as u32,
+  };
+
+  let reboot_delay = reboot_delay();
+  if reboot_delay != 0 {
+    watchdog::start_watchdog(reboot_delay);
+  }
+
+  // With interrupts masked the console falls back to polled output, so this works from any context.
+  if console::is_initialized() {
+    let micros = timer_counter();
+    let mut console = Console;
+    let _ = writeln!(console, "\n[{:5}.{:06}] KERNEL PANIC: {}", micros / 1_000_000, micros % 1_000_000, info.message());
+    if let Some(location) = info.location() {
+      let _ = writeln!(console, "  at {}:{}:{}", location.file(), location.line(), location.column());
+    }
+    match &exception {
+      Some((kind, context)) => {
+        let _ = writeln!(console, "Registers at {} exception:\n{}", kind.name(), context);
+        // FIQ mode banks r8-r12, so the interrupted code's frame pointer isn't in the saved context.
+        let fp = if *kind == ExceptionKind::Fiq { 0 } else { context.r[11] };
+        let _ = backtrace::write_backtrace(&mut console, context.pc, fp);
+      }
+      None => {
+        let _ = writeln!(console, "Registers in panic handler:\n{}", registers);
+        let _ = backtrace::write_backtrace(&mut console, registers.pc, registers.r[11]);
+      }
+    }
+    if reboot_delay != 0 {
+      let _ = writeln!(console, "Rebooting in {} seconds.", reboot_delay);
+    } else {
+      let _ = writeln!(console, "System halted, blinking error code {} on the ACT LED.", code);
+    }
+    console::flush();
+  }
+
+  blink_forever(code);
+}
+
+/// Blinks `code` short flashes on the ACT LED, followed by a pause, forever.
+fn blink_forever(code: u32) -> ! {
+  gpio::pin_function_set(ACT_LED, PinFunction::OUTPUT);
+  // Busy waits on the system timer, IRQs are masked so nothing interrupt-driven can be used here.
+  loop {
+    for _ in 0..code {
+      gpio::pin_output_set(ACT_LED);
+      time::delay_ms(200);
+      gpio::pin_output_clear(ACT_LED);
+      time::delay_ms(300);
+    }
+    time::delay_ms(1500);
+  }
+}
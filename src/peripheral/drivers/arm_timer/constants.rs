@@ -0,0 +1,86 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
+
+use crate::util::mem::{NamedRegister, Register};
+
+const BASE: u32 = 0x7E00B400;
+
+/// ARM Timer Load
+///
+/// Writing sets the value the timer counts down from, and restarts the count immediately.
+pub const ARM_TIMER_LOAD: Register = Register::from_addr(BASE);
+/// ARM Timer Value (read only)
+///
+/// The current value of the down counter.
+pub const ARM_TIMER_VALUE: Register = Register::from_addr(BASE + 0x04);
+/// ARM Timer Control
+///
+/// Bit 1 selects the counter width (0 = 16 bits, 1 = 32 bits), bits 3:2 the prescaler,
+/// bit 5 enables the interrupt, bit 7 enables the timer, bit 9 enables the free running counter.
+/// Bits 23:16 are the free running counter's prescaler, see [ARM_TIMER_FREE_RUNNING_COUNTER].
+pub const ARM_TIMER_CONTROL: Register = Register::from_addr(BASE + 0x08);
+/// ARM Timer IRQ Clear (write only)
+///
+/// Writing any value clears the interrupt.
+pub const ARM_TIMER_IRQ_CLEAR: Register = Register::from_addr(BASE + 0x0C);
+/// ARM Timer Raw IRQ (read only)
+///
+/// Bit 0 is set when the counter has reached 0, whether the interrupt is enabled or not.
+pub const ARM_TIMER_RAW_IRQ: Register = Register::from_addr(BASE + 0x10);
+/// ARM Timer Masked IRQ (read only)
+///
+/// Bit 0 is set when the interrupt is pending, i.e. the raw interrupt and the enable bit are both set.
+pub const ARM_TIMER_MASKED_IRQ: Register = Register::from_addr(BASE + 0x14);
+/// ARM Timer Reload
+///
+/// Same as [ARM_TIMER_LOAD], but only takes effect the next time the counter reaches 0.
+pub const ARM_TIMER_RELOAD: Register = Register::from_addr(BASE + 0x18);
+/// ARM Timer Pre-divider
+///
+/// Bits 9:0 divide the APB clock before it reaches the timer: timer clock = APB clock / (pre-divider + 1).
+/// Not part of the SP804 the timer is based on. Resets to 0x7D.
+pub const ARM_TIMER_PREDIVIDER: Register = Register::from_addr(BASE + 0x1C);
+/// ARM Timer Free Running Counter (read only)
+///
+/// Counts up from the APB clock divided by the free running prescaler in [ARM_TIMER_CONTROL] plus 1,
+/// independently of the down counter. Wraps at 32 bits.
+pub const ARM_TIMER_FREE_RUNNING_COUNTER: Register = Register::from_addr(BASE + 0x20);
+
+/// The ARM timer runs off the APB clock, which is the VPU core clock. It differs between boards (the Pi Zero's is
+/// 400 MHz) and with `core_freq` in config.txt, so it's asked from the firmware. This is only used if that fails.
+pub const APB_CLOCK_HZ: u32 = 250_000_000;
+
+/// Largest value of [ARM_TIMER_PREDIVIDER]
+pub const PREDIVIDER_MAX: u32 = 0x3FF;
+/// Largest free running counter prescaler
+pub const FREE_RUNNING_PRESCALER_MAX: u32 = 0xFF;
+
+// These are for reference only, to avoid magic numbers in the code.
+// They should not be used anywhere else, so we use pub(in super) to limit their visibility.
+pub(in super) mod bits {
+  /// 32 bit counter (instead of 16 bit), in ARM_TIMER_CONTROL
+  pub const CONTROL_32_BIT: u32 = 1;
+  /// Prescaler field shift, in ARM_TIMER_CONTROL
+  pub const CONTROL_PRESCALER_SHIFT: u32 = 2;
+  /// Prescaler field mask, in ARM_TIMER_CONTROL
+  pub const CONTROL_PRESCALER_MASK: u32 = 0b11 << CONTROL_PRESCALER_SHIFT;
+  /// Interrupt enable, in ARM_TIMER_CONTROL
+  pub const CONTROL_INTERRUPT_ENABLE: u32 = 5;
+  /// Timer enable, in ARM_TIMER_CONTROL
+  pub const CONTROL_TIMER_ENABLE: u32 = 7;
+  /// Free running counter enable, in ARM_TIMER_CONTROL
+  pub const CONTROL_FREE_RUNNING_ENABLE: u32 = 9;
+  /// Free running counter prescaler field shift, in ARM_TIMER_CONTROL
+  pub const CONTROL_FREE_RUNNING_PRESCALER_SHIFT: u32 = 16;
+  /// Free running counter prescaler field mask, in ARM_TIMER_CONTROL
+  pub const CONTROL_FREE_RUNNING_PRESCALER_MASK: u32 = 0xFF << CONTROL_FREE_RUNNING_PRESCALER_SHIFT;
+
+  /// Interrupt pending, in ARM_TIMER_RAW_IRQ and ARM_TIMER_MASKED_IRQ
+  pub const IRQ_PENDING: u32 = 0;
+}
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  ARM_TIMER_LOAD, ARM_TIMER_VALUE, ARM_TIMER_CONTROL, ARM_TIMER_IRQ_CLEAR, ARM_TIMER_RAW_IRQ, ARM_TIMER_MASKED_IRQ,
+  ARM_TIMER_RELOAD, ARM_TIMER_PREDIVIDER, ARM_TIMER_FREE_RUNNING_COUNTER,
+];
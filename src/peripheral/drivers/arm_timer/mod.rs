@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! The ARM timer, an SP804 derivative with a down counter that interrupts when it reaches 0 and a free running counter.
+//!
+//! Unlike the system timer, it isn't shared with the GPU, which makes it a good periodic tick source. Both counters run
+//! off the APB clock though, so they slow down if the firmware scales the core clock, see [arm_timer_clock_rate].
+//!
+//! ```ignore
+//! interrupt::register_handler(IrqSource::ArmTimer, || {
+//!   arm_timer::arm_timer_clear_interrupt();
+//!   // ...
+//! });
+//! interrupt::enable(IrqSource::ArmTimer);
+//! arm_timer::arm_timer_start_periodic(Duration::from_millis(10))?;
+//! ```
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use core::time::Duration;
+
+use self::constants::bits;
+use crate::peripheral::drivers::mailbox::{self, constants::Clock};
+
+pub mod constants;
+
+/// Divides the timer clock further, on top of the pre-divider.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Prescaler {
+  Div1 = 0b00,
+  Div16 = 0b01,
+  Div256 = 0b10,
+}
+
+impl Prescaler {
+  pub fn divisor(&self) -> u32 {
+    match self {
+      Prescaler::Div1 => 1,
+      Prescaler::Div16 => 16,
+      Prescaler::Div256 => 256,
+    }
+  }
+}
+
+/// Width of the down counter. In 16 bit mode only the lower 16 bits of the load value are used.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CounterWidth {
+  Bits16,
+  Bits32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArmTimerError {
+  /// The period is zero, or too long for the counter at the lowest tick rate
+  UnsupportedPeriod,
+}
+
+/// Rate the periodic tick counts at, see [arm_timer_start_periodic].
+const TICK_RATE_HZ: u32 = 1_000_000;
+
+/// Stops the timer and sets the counter width and prescaler. The interrupt is left disabled.
+pub fn arm_timer_configure(width: CounterWidth, prescaler: Prescaler) {
+  let control = constants::ARM_TIMER_CONTROL.read();
+  // Only the free running counter's settings are kept.
+  let mut control = control & ((1 << bits::CONTROL_FREE_RUNNING_ENABLE) | bits::CONTROL_FREE_RUNNING_PRESCALER_MASK);
+  if width == CounterWidth::Bits32 {
+    control |= 1 << bits::CONTROL_32_BIT;
+  }
+  control |= (prescaler as u32) << bits::CONTROL_PRESCALER_SHIFT;
+  constants::ARM_TIMER_CONTROL.write(control);
+}
+
+/// Sets the pre-divider, the timer clock being the APB clock divided by `predivider + 1`.
+/// Values above [constants::PREDIVIDER_MAX] are clamped.
+pub fn arm_timer_set_predivider(predivider: u32) {
+  constants::ARM_TIMER_PREDIVIDER.write(predivider.min(constants::PREDIVIDER_MAX));
+}
+
+pub fn arm_timer_predivider() -> u32 {
+  constants::ARM_TIMER_PREDIVIDER.read() & constants::PREDIVIDER_MAX
+}
+
+/// Sets the value the counter counts down from and restarts the count. The timer fires every `value + 1` ticks.
+pub fn arm_timer_set_load(value: u32) {
+  constants::ARM_TIMER_LOAD.write(value);
+}
+
+/// Sets the value the counter counts down from, starting the next time it reaches 0, without disturbing the current count.
+pub fn arm_timer_set_reload(value: u32) {
+  constants::ARM_TIMER_RELOAD.write(value);
+}
+
+/// Current value of the down counter.
+pub fn arm_timer_value() -> u32 {
+  constants::ARM_TIMER_VALUE.read()
+}
+
+pub fn arm_timer_start() {
+  constants::ARM_TIMER_CONTROL.write_bit(bits::CONTROL_TIMER_ENABLE, 1);
+}
+
+pub fn arm_timer_stop() {
+  constants::ARM_TIMER_CONTROL.write_bit(bits::CONTROL_TIMER_ENABLE, 0);
+}
+
+pub fn arm_timer_is_running() -> bool {
+  constants::ARM_TIMER_CONTROL.read_bit(bits::CONTROL_TIMER_ENABLE)
+}
+
+/// Lets the timer raise [IrqSource::ArmTimer](crate::peripheral::drivers::interrupt::constants::IrqSource::ArmTimer)
+/// when the counter reaches 0. The source also has to be enabled in the interrupt controller.
+pub fn arm_timer_enable_interrupt() {
+  constants::ARM_TIMER_CONTROL.write_bit(bits::CONTROL_INTERRUPT_ENABLE, 1);
+}
+
+pub fn arm_timer_disable_interrupt() {
+  constants::ARM_TIMER_CONTROL.write_bit(bits::CONTROL_INTERRUPT_ENABLE, 0);
+}
+
+/// Clears the interrupt. The handler has to do this, or the interrupt fires again as soon as it returns.
+#[inline]
+pub fn arm_timer_clear_interrupt() {
+  // Any value clears it.
+  constants::ARM_TIMER_IRQ_CLEAR.write(0);
+}
+
+/// Whether the counter has reached 0 since the interrupt was last cleared, regardless of whether the interrupt is enabled.
+#[inline]
+pub fn arm_timer_has_triggered() -> bool {
+  constants::ARM_TIMER_RAW_IRQ.read_bit(bits::IRQ_PENDING)
+}
+
+/// Whether the interrupt is pending, i.e. it's enabled and the counter has reached 0.
+#[inline]
+pub fn arm_timer_interrupt_pending() -> bool {
+  constants::ARM_TIMER_MASKED_IRQ.read_bit(bits::IRQ_PENDING)
+}
+
+/// Starts the free running counter, counting at the APB clock divided by `prescaler + 1`.
+/// Values above [constants::FREE_RUNNING_PRESCALER_MAX] are clamped.
+pub fn arm_timer_free_running_enable(prescaler: u32) {
+  let control = constants::ARM_TIMER_CONTROL.read() & !bits::CONTROL_FREE_RUNNING_PRESCALER_MASK;
+  let prescaler = prescaler.min(constants::FREE_RUNNING_PRESCALER_MAX) << bits::CONTROL_FREE_RUNNING_PRESCALER_SHIFT;
+  constants::ARM_TIMER_CONTROL.write(control | prescaler | (1 << bits::CONTROL_FREE_RUNNING_ENABLE));
+}
+
+pub fn arm_timer_free_running_disable() {
+  constants::ARM_TIMER_CONTROL.write_bit(bits::CONTROL_FREE_RUNNING_ENABLE, 0);
+}
+
+#[inline]
+pub fn arm_timer_free_running_counter() -> u32 {
+  constants::ARM_TIMER_FREE_RUNNING_COUNTER.read()
+}
+
+/// The APB clock the timer runs off in Hz, as reported by the firmware, or [constants::APB_CLOCK_HZ] if it can't be
+/// asked. Must not be called from interrupt handlers, see [mailbox::mailbox_call].
+pub fn arm_timer_clock_rate() -> u32 {
+  match mailbox::clock_rate(Clock::Core) {
+    Ok(rate) if rate != 0 => rate,
+    _ => constants::APB_CLOCK_HZ,
+  }
+}
+
+/// Runs the timer in 32 bit mode with its interrupt enabled, firing every `period`.
+/// The interrupt source has to be enabled and handled separately, see the example in the module docs.
+///
+/// The timer counts at 1 MHz if the APB clock allows it, so the period is rounded down to whole microseconds.
+/// The clock is taken from [arm_timer_clock_rate], so this must not be called from interrupt handlers either.
+pub fn arm_timer_start_periodic(period: Duration) -> Result<(), ArmTimerError> {
+  let period_us = period.as_micros().min(u64::MAX as u128) as u64;
+  let (predivider, load) =
+    periodic_settings(arm_timer_clock_rate(), period_us).ok_or(ArmTimerError::UnsupportedPeriod)?;
+  arm_timer_stop();
+  arm_timer_configure(CounterWidth::Bits32, Prescaler::Div1);
+  arm_timer_set_predivider(predivider);
+  arm_timer_set_load(load);
+  arm_timer_set_reload(load);
+  arm_timer_clear_interrupt();
+  arm_timer_enable_interrupt();
+  arm_timer_start();
+  Ok(())
+}
+
+/// The pre-divider and load value for a period of `period_us` microseconds, given an APB clock of `clock_hz`.
+///
+/// The pre-divider brings the timer clock as close to [TICK_RATE_HZ] as it can get from above.
+const fn periodic_settings(clock_hz: u32, period_us: u64) -> Option<(u32, u32)> {
+  let mut predivider = (clock_hz / TICK_RATE_HZ).saturating_sub(1);
+  if predivider > constants::PREDIVIDER_MAX {
+    predivider = constants::PREDIVIDER_MAX;
+  }
+  let tick_hz = (clock_hz / (predivider + 1)) as u64;
+  let Some(ticks) = period_us.checked_mul(tick_hz) else {
+    return None;
+  };
+  let ticks = ticks / 1_000_000;
+  if ticks == 0 || ticks > u32::MAX as u64 + 1 {
+    return None;
+  }
+  Some((predivider, (ticks - 1) as u32))
+}
+
+// Sanity checks. Compile-time assertions, doesn't create any extra runtime code.
+const _: () = assert!(
+  matches!(periodic_settings(250_000_000, 10_000), Some((249, 9_999))),
+  "A 250 MHz clock is divided down to 1 MHz, ticking every 10000 microseconds",
+);
+const _: () = assert!(
+  matches!(periodic_settings(400_000_000, 1), Some((399, 0))),
+  "A period of one tick has a load value of 0",
+);
+const _: () = assert!(periodic_settings(250_000_000, 0).is_none(), "A zero period isn't supported");
+const _: () = assert!(
+  periodic_settings(250_000_000, u32::MAX as u64 + 2).is_none(),
+  "Periods longer than the 32 bit counter aren't supported",
+);
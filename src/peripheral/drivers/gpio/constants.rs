@@ -1,7 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
 
-use crate::util::mem::Register;
+use crate::util::mem::{NamedRegister, Register};
 
 #[derive(Clone, Copy)]
 pub enum PinFunction {
@@ -31,6 +31,65 @@ impl PinFunction {
   }
 }
 
+/// Pull-up/down control, the value for [GPIO_PUD].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pull {
+  Off,
+  Down,
+  Up,
+}
+
+impl Pull {
+  #[inline]
+  pub fn value(&self) -> u32 {
+    match *self {
+      Pull::Off => 0b00,
+      Pull::Down => 0b01,
+      Pull::Up => 0b10,
+    }
+  }
+}
+
+/// Events the pins can detect, each with its own enable registers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+  /// Synchronous rising edge, see [GPIO_REN0]
+  RisingEdge,
+  /// Synchronous falling edge, see [GPIO_FEN0]
+  FallingEdge,
+  /// Pin is high, see [GPIO_HEN0]
+  High,
+  /// Pin is low, see [GPIO_LEN0]
+  Low,
+  /// Asynchronous rising edge, see [GPIO_AREN0]
+  AsyncRisingEdge,
+  /// Asynchronous falling edge, see [GPIO_AFEN0]
+  AsyncFallingEdge,
+}
+
+impl Event {
+  /// The enable registers for banks 0 and 1.
+  #[inline]
+  pub fn registers(&self) -> [Register; 2] {
+    match *self {
+      Event::RisingEdge => [GPIO_REN0, GPIO_REN1],
+      Event::FallingEdge => [GPIO_FEN0, GPIO_FEN1],
+      Event::High => [GPIO_HEN0, GPIO_HEN1],
+      Event::Low => [GPIO_LEN0, GPIO_LEN1],
+      Event::AsyncRisingEdge => [GPIO_AREN0, GPIO_AREN1],
+      Event::AsyncFallingEdge => [GPIO_AFEN0, GPIO_AFEN1],
+    }
+  }
+}
+
+/// Number of GPIO pins, 0-53.
+pub const PIN_COUNT: u32 = 54;
+/// Pins are split into banks of 32, with one bit per pin in the level, set, clear, event and pull clock registers.
+pub const BANK_COUNT: u32 = PIN_COUNT.div_ceil(32);
+
+/// Cycles to wait between the steps of setting a pull, see [super::pin_pull_set].
+pub const PULL_SETUP_CYCLES: u32 = 150;
+
 const BASE: u32 = 0x7E200000;
 
 /// Function Select 0 (pins 0-9)
@@ -149,38 +208,70 @@ pub const GPIO_LEV0: Register = Register::from_addr(BASE + 0x34);
 /// value of the respective GPIO pin.
 pub const GPIO_LEV1: Register = Register::from_addr(BASE + 0x38);
 
-// TODO: Document constants below
-/// Pin Event Detect Status 0
+/// Pin Event Detect Status 0 (pins 0-31)
+///
+/// A bit is set when the respective pin had an event enabled in one of the detect enable registers
+/// ([GPIO_REN0], [GPIO_FEN0], [GPIO_HEN0], [GPIO_LEN0], [GPIO_AREN0], [GPIO_AFEN0]).
+/// Writing a 1 to a bit clears it. Set bits raise the GPIO interrupts, if they are enabled.
 pub const GPIO_EDS0: Register = Register::from_addr(BASE + 0x40);
-/// Pin Event Detect Status 1
+/// Pin Event Detect Status 1 (pins 32-53), see [GPIO_EDS0]
 pub const GPIO_EDS1: Register = Register::from_addr(BASE + 0x44);
-/// Pin Rising Edge Detect Enable 0
+/// Pin Rising Edge Detect Enable 0 (pins 0-31)
+///
+/// Setting a bit makes a rising edge on the pin set its bit in [GPIO_EDS0]. The input is sampled with the
+/// system clock, looking for 011, so glitches are filtered out.
 pub const GPIO_REN0: Register = Register::from_addr(BASE + 0x4C);
-/// Pin Rising Edge Detect Enable 1
+/// Pin Rising Edge Detect Enable 1 (pins 32-53), see [GPIO_REN0]
 pub const GPIO_REN1: Register = Register::from_addr(BASE + 0x50);
-/// Pin Falling Edge Detect Enable 0
+/// Pin Falling Edge Detect Enable 0 (pins 0-31)
+///
+/// Same as [GPIO_REN0] for falling edges, looking for 100.
 pub const GPIO_FEN0: Register = Register::from_addr(BASE + 0x58);
-/// Pin Falling Edge Detect Enable 1
+/// Pin Falling Edge Detect Enable 1 (pins 32-53), see [GPIO_FEN0]
 pub const GPIO_FEN1: Register = Register::from_addr(BASE + 0x5C);
-/// Pin High Detect Enable 0
+/// Pin High Detect Enable 0 (pins 0-31)
+///
+/// Setting a bit sets the pin's bit in [GPIO_EDS0] for as long as the pin is high,
+/// clearing the status bit doesn't help until the pin goes low.
 pub const GPIO_HEN0: Register = Register::from_addr(BASE + 0x64);
-/// Pin High Detect Enable 1
+/// Pin High Detect Enable 1 (pins 32-53), see [GPIO_HEN0]
 pub const GPIO_HEN1: Register = Register::from_addr(BASE + 0x68);
-/// Pin Low Detect Enable 0
+/// Pin Low Detect Enable 0 (pins 0-31)
+///
+/// Same as [GPIO_HEN0] for as long as the pin is low.
 pub const GPIO_LEN0: Register = Register::from_addr(BASE + 0x70);
-/// Pin Low Detect Enable 1
+/// Pin Low Detect Enable 1 (pins 32-53), see [GPIO_LEN0]
 pub const GPIO_LEN1: Register = Register::from_addr(BASE + 0x74);
-/// Pin Async. Rising Edge Detect 0
+/// Pin Async. Rising Edge Detect 0 (pins 0-31)
+///
+/// Same as [GPIO_REN0], but the input isn't sampled, so very short pulses are seen too.
 pub const GPIO_AREN0: Register = Register::from_addr(BASE + 0x7C);
-/// Pin Async. Rising Edge Detect 1
+/// Pin Async. Rising Edge Detect 1 (pins 32-53), see [GPIO_AREN0]
 pub const GPIO_AREN1: Register = Register::from_addr(BASE + 0x80);
-/// Pin Async. Falling Edge Detect 0
+/// Pin Async. Falling Edge Detect 0 (pins 0-31)
+///
+/// Same as [GPIO_FEN0], but the input isn't sampled, so very short pulses are seen too.
 pub const GPIO_AFEN0: Register = Register::from_addr(BASE + 0x88);
-/// Pin Async. Falling Edge Detect 1
+/// Pin Async. Falling Edge Detect 1 (pins 32-53), see [GPIO_AFEN0]
 pub const GPIO_AFEN1: Register = Register::from_addr(BASE + 0x8C);
 /// Pin Pull-up/down Enable
+///
+/// Bits 1:0 select the pull applied to the pins clocked with [GPIO_PUDCLK0] and [GPIO_PUDCLK1], see [Pull].
+/// The pull is only applied while clocking, see [super::pin_pull_set] for the sequence.
 pub const GPIO_PUD: Register = Register::from_addr(BASE + 0x94);
-/// Pin Pull-up/down Enable Clock 0
+/// Pin Pull-up/down Enable Clock 0 (pins 0-31)
+///
+/// Setting a bit clocks the pull in [GPIO_PUD] into the pin. The pins keep their pull after the bit is cleared,
+/// and through a reset, but not through a power cycle.
 pub const GPIO_PUDCLK0: Register = Register::from_addr(BASE + 0x98);
-/// Pin Pull-up/down Enable Clock 1
+/// Pin Pull-up/down Enable Clock 1 (pins 32-53), see [GPIO_PUDCLK0]
 pub const GPIO_PUDCLK1: Register = Register::from_addr(BASE + 0x9C);
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  GPIO_FSEL0, GPIO_FSEL1, GPIO_FSEL2, GPIO_FSEL3, GPIO_FSEL4, GPIO_FSEL5,
+  GPIO_SET0, GPIO_SET1, GPIO_CLR0, GPIO_CLR1, GPIO_LEV0, GPIO_LEV1,
+  GPIO_EDS0, GPIO_EDS1, GPIO_REN0, GPIO_REN1, GPIO_FEN0, GPIO_FEN1,
+  GPIO_HEN0, GPIO_HEN1, GPIO_LEN0, GPIO_LEN1, GPIO_AREN0, GPIO_AREN1,
+  GPIO_AFEN0, GPIO_AFEN1, GPIO_PUD, GPIO_PUDCLK0, GPIO_PUDCLK1,
+];
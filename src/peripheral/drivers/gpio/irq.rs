@@ -0,0 +1,116 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Per-pin interrupt handlers, dispatched from the GPIO bank interrupts.
+//!
+//! ```ignore
+//! gpio::irq::register(17, Trigger::Falling, 20, |pin, _| info!("Button on pin {} pressed", pin));
+//! ```
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use super::constants::{Event, BANK_COUNT, PIN_COUNT};
+use crate::peripheral::drivers::interrupt::{self, constants::IrqSource};
+use crate::sync::SpinLock;
+use crate::time::{Duration, Instant};
+
+/// Called when a pin sees the edge it was registered for, with the pin number and its level at the time.
+/// Handlers run in the IRQ handler with IRQs masked, so they should be quick.
+pub type PinHandler = fn(pin: u32, high: bool);
+
+/// Edges a pin interrupt fires on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Trigger {
+  Rising,
+  Falling,
+  Both,
+}
+
+impl Trigger {
+  fn events(&self) -> &'static [Event] {
+    match *self {
+      Trigger::Rising => &[Event::RisingEdge],
+      Trigger::Falling => &[Event::FallingEdge],
+      Trigger::Both => &[Event::RisingEdge, Event::FallingEdge],
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+struct PinIrq {
+  handler: PinHandler,
+  trigger: Trigger,
+  debounce: Duration,
+  /// When the handler was last called
+  last: Option<Instant>,
+}
+
+static PIN_IRQS: SpinLock<[Option<PinIrq>; PIN_COUNT as usize]> = SpinLock::new([None; PIN_COUNT as usize]);
+
+const BANK_SOURCES: [IrqSource; BANK_COUNT as usize] = [IrqSource::Gpio0, IrqSource::Gpio1];
+const BANK_HANDLERS: [fn(); BANK_COUNT as usize] = [|| handle_bank(0), || handle_bank(1)];
+
+/// Calls `handler` whenever `pin` sees `trigger`, replacing the pin's previous handler.
+///
+/// Edges less than `debounce_ms` milliseconds after the last one that was handled are ignored, 0 handles every edge.
+/// The pin should be an input. Requires the interrupt controller to be initialized, see [interrupt::init].
+pub fn register(pin: u32, trigger: Trigger, debounce_ms: u32, handler: PinHandler) {
+  let (bank, mask) = super::bank_of(pin);
+  // The bank's interrupt is enabled under the same lock, so a concurrent [unregister] can't disable it in between.
+  let mut irqs = PIN_IRQS.lock();
+  remove(&mut irqs, pin);
+  irqs[pin as usize] = Some(PinIrq { handler, trigger, debounce: Duration::from_millis(debounce_ms as u64), last: None });
+
+  // Forget edges from before the handler was registered.
+  super::bank_event_clear(bank, mask);
+  for &event in trigger.events() {
+    super::bank_event_enable(bank, mask, event);
+  }
+  interrupt::register_handler(BANK_SOURCES[bank as usize], BANK_HANDLERS[bank as usize]);
+  interrupt::enable(BANK_SOURCES[bank as usize]);
+}
+
+/// Stops calling the pin's handler. The bank's interrupt is disabled once none of its pins have handlers.
+pub fn unregister(pin: u32) {
+  remove(&mut PIN_IRQS.lock(), pin);
+}
+
+// Removes the pin's handler, disabling the bank's interrupt if it was the last one. The caller holds PIN_IRQS
+// throughout, so no other pin of the bank can be registered between the check and disabling it.
+fn remove(irqs: &mut [Option<PinIrq>; PIN_COUNT as usize], pin: u32) {
+  let (bank, mask) = super::bank_of(pin);
+  let Some(irq) = irqs[pin as usize].take() else {
+    return;
+  };
+  for &event in irq.trigger.events() {
+    super::bank_event_disable(bank, mask, event);
+  }
+  super::bank_event_clear(bank, mask);
+
+  let bank_pins = bank * 32..(bank * 32 + 32).min(PIN_COUNT);
+  if irqs[bank_pins.start as usize..bank_pins.end as usize].iter().all(Option::is_none) {
+    interrupt::disable(BANK_SOURCES[bank as usize]);
+  }
+}
+
+fn handle_bank(bank: u32) {
+  let status = super::bank_event_status(bank);
+  super::bank_event_clear(bank, status);
+  let now = Instant::now();
+
+  let mut bits = status;
+  while bits != 0 {
+    let bit = bits.trailing_zeros();
+    bits &= bits - 1;
+    let pin = bank * 32 + bit;
+
+    let handler = PIN_IRQS.with(|irqs| {
+      let irq = irqs[pin as usize].as_mut()?;
+      if irq.last.is_some_and(|last| now - last < irq.debounce) {
+        return None;
+      }
+      irq.last = Some(now);
+      Some(irq.handler)
+    });
+    if let Some(handler) = handler {
+      handler(pin, super::pin_level(pin));
+    }
+  }
+}
@@ -1,10 +1,13 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
 
-use self::constants::PinFunction;
+use self::constants::{Event, PinFunction, Pull, BANK_COUNT, PIN_COUNT, PULL_SETUP_CYCLES};
+use crate::util::cpu;
 use crate::util::mem::Register;
 
 pub mod constants;
+pub mod irq;
+pub mod pin;
 
 pub fn pin_function_set(pin: u32, function: PinFunction) -> () {
   let fsel_register: Register = match pin {
@@ -29,21 +32,128 @@ pub fn pin_function_set(pin: u32, function: PinFunction) -> () {
 }
 
 pub fn pin_output_set(pin: u32) -> () {
-  let set_register: Register = match pin {
-    0..=31 => constants::GPIO_SET0,
-    32..=53 => constants::GPIO_SET1,
-    _ => panic!("Invalid GPIO pin {}", pin),
-  };
-
-  set_register.write_bit(pin % 32, 1);
+  let (bank, mask) = bank_of(pin);
+  bank_output_set(bank, mask);
 }
 
 pub fn pin_output_clear(pin: u32) -> () {
-  let clear_register: Register = match pin {
-    0..=31 => constants::GPIO_CLR0,
-    32..=53 => constants::GPIO_CLR1,
-    _ => panic!("Invalid GPIO pin {}", pin),
-  };
+  let (bank, mask) = bank_of(pin);
+  bank_output_clear(bank, mask);
+}
+
+/// Whether the pin is high, whatever its function.
+pub fn pin_level(pin: u32) -> bool {
+  let (bank, mask) = bank_of(pin);
+  bank_level(bank) & mask != 0
+}
+
+/// Sets the pin's pull-up/down, which it keeps until it's changed or the board loses power.
+pub fn pin_pull_set(pin: u32, pull: Pull) {
+  let (bank, mask) = bank_of(pin);
+  bank_pull_set(bank, mask, pull);
+}
+
+/// Makes the pin set its event status bit on `event`, see [pin_event_detected].
+pub fn pin_event_enable(pin: u32, event: Event) {
+  let (bank, mask) = bank_of(pin);
+  bank_event_enable(bank, mask, event);
+}
+
+pub fn pin_event_disable(pin: u32, event: Event) {
+  let (bank, mask) = bank_of(pin);
+  bank_event_disable(bank, mask, event);
+}
+
+/// Whether one of the pin's enabled events happened since its status was last cleared.
+pub fn pin_event_detected(pin: u32) -> bool {
+  let (bank, mask) = bank_of(pin);
+  bank_event_status(bank) & mask != 0
+}
+
+pub fn pin_event_clear(pin: u32) {
+  let (bank, mask) = bank_of(pin);
+  bank_event_clear(bank, mask);
+}
+
+// Bank operations work on 32 pins at once, bank 0 being pins 0-31 and bank 1 pins 32-53.
+// Bit n of a mask is pin `bank * 32 + n`, bits of pins that don't exist are ignored.
+
+/// Drives the masked pins high, if they're outputs.
+pub fn bank_output_set(bank: u32, mask: u32) {
+  bank_register([constants::GPIO_SET0, constants::GPIO_SET1], bank).write(mask & bank_pins(bank));
+}
+
+/// Drives the masked pins low, if they're outputs.
+pub fn bank_output_clear(bank: u32, mask: u32) {
+  bank_register([constants::GPIO_CLR0, constants::GPIO_CLR1], bank).write(mask & bank_pins(bank));
+}
+
+/// Levels of the bank's pins, a set bit being a high pin.
+pub fn bank_level(bank: u32) -> u32 {
+  bank_register([constants::GPIO_LEV0, constants::GPIO_LEV1], bank).read() & bank_pins(bank)
+}
+
+/// Sets the pull-up/down of the masked pins, using the sequence from the datasheet:
+/// set the pull, wait, clock it into the pins, wait, and remove the pull and the clock.
+pub fn bank_pull_set(bank: u32, mask: u32, pull: Pull) {
+  let clock = bank_register([constants::GPIO_PUDCLK0, constants::GPIO_PUDCLK1], bank);
+  // GPIO_PUD is shared by both banks, nothing else may use it until the sequence is done.
+  cpu::without_interrupts(|| {
+    constants::GPIO_PUD.write(pull.value());
+    cpu::delay_cycles(PULL_SETUP_CYCLES);
+    clock.write(mask & bank_pins(bank));
+    cpu::delay_cycles(PULL_SETUP_CYCLES);
+    constants::GPIO_PUD.write(Pull::Off.value());
+    clock.write(0);
+  });
+}
+
+pub fn bank_event_enable(bank: u32, mask: u32, event: Event) {
+  let register = bank_register(event.registers(), bank);
+  cpu::without_interrupts(|| register.write(register.read() | (mask & bank_pins(bank))));
+}
+
+pub fn bank_event_disable(bank: u32, mask: u32, event: Event) {
+  let register = bank_register(event.registers(), bank);
+  cpu::without_interrupts(|| register.write(register.read() & !mask));
+}
+
+/// Pins of the bank that detected an enabled event since their status was last cleared.
+pub fn bank_event_status(bank: u32) -> u32 {
+  bank_register([constants::GPIO_EDS0, constants::GPIO_EDS1], bank).read() & bank_pins(bank)
+}
+
+/// Clears the event status of the masked pins.
+pub fn bank_event_clear(bank: u32, mask: u32) {
+  // Writing a 1 clears the bit, zeroes leave the other pins alone.
+  bank_register([constants::GPIO_EDS0, constants::GPIO_EDS1], bank).write(mask & bank_pins(bank));
+}
 
-  clear_register.write_bit(pin % 32, 1);
+/// Bank and mask bit of a pin.
+#[inline]
+fn bank_of(pin: u32) -> (u32, u32) {
+  if pin >= PIN_COUNT {
+    panic!("Invalid GPIO pin {}", pin);
+  }
+  (pin / 32, 1 << (pin % 32))
 }
+
+/// Mask of the pins that exist in the bank.
+#[inline]
+fn bank_pins(bank: u32) -> u32 {
+  match PIN_COUNT - bank * 32 {
+    32.. => u32::MAX,
+    count => (1 << count) - 1,
+  }
+}
+
+#[inline]
+fn bank_register(registers: [Register; 2], bank: u32) -> Register {
+  match registers.get(bank as usize) {
+    Some(register) => *register,
+    None => panic!("Invalid GPIO bank {}", bank),
+  }
+}
+
+// Sanity checks. Compile-time assertions, doesn't create any extra runtime code.
+const _: () = assert!(BANK_COUNT == 2, "The bank registers come in pairs");
@@ -0,0 +1,273 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! GPIO pins as owned values, with their mode in the type.
+//!
+//! [Pins::take] hands out every pin once, so two drivers can't end up driving the same pin,
+//! and a pin can only be used the way its mode allows:
+//!
+//! ```ignore
+//! let pins = Pins::take().unwrap();
+//! let mut led = pins.p18.into_output();
+//! let button = pins.p27.into_input_pullup();
+//! led.set_state(button.is_low().into());
+//! ```
+//!
+//! The raw functions in [super] don't check ownership, they're for the kernel's own use of pins it keeps out of [Pins].
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::constants::{PinFunction, Pull, PIN_COUNT};
+use super::irq::{self, PinHandler, Trigger};
+use crate::peripheral::hal::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
+
+/// Mode of a pin that hasn't been configured since it was taken, it's in whatever state it was left in.
+pub struct Unknown;
+/// Mode of an input pin, with its pull.
+pub struct Input<PULL>(PhantomData<PULL>);
+/// Pull of an input pin with no pull-up/down.
+pub struct Floating;
+pub struct PullUp;
+pub struct PullDown;
+/// Mode of an output pin.
+pub struct Output;
+/// Mode of a pin connected to one of its alternative functions, 0-5.
+pub struct Alternate<const F: u8>;
+
+/// GPIO pin `N`, in mode `MODE`.
+pub struct Pin<const N: u8, MODE> {
+  mode: PhantomData<MODE>,
+}
+
+impl<const N: u8, MODE> Pin<N, MODE> {
+  /// Fails to compile for pins that don't exist.
+  const VALID: () = assert!((N as u32) < PIN_COUNT, "GPIO pin number out of range");
+
+  fn new() -> Self {
+    let () = Self::VALID;
+    Self { mode: PhantomData }
+  }
+
+  /// Pin `N`, without going through [Pins].
+  ///
+  /// SAFETY: Nothing else may be using the pin.
+  pub unsafe fn steal() -> Self {
+    Self::new()
+  }
+
+  pub fn number(&self) -> u8 {
+    N
+  }
+
+  pub fn into_input(self) -> Pin<N, Input<Floating>> {
+    self.into_input_with(Pull::Off)
+  }
+
+  pub fn into_input_pullup(self) -> Pin<N, Input<PullUp>> {
+    self.into_input_with(Pull::Up)
+  }
+
+  pub fn into_input_pulldown(self) -> Pin<N, Input<PullDown>> {
+    self.into_input_with(Pull::Down)
+  }
+
+  /// Makes the pin an output, driving it low.
+  pub fn into_output(self) -> Pin<N, Output> {
+    // Set the level first, so the pin doesn't glitch to whatever it was left at.
+    super::pin_output_clear(N as u32);
+    self.into_function(PinFunction::OUTPUT)
+  }
+
+  /// Makes the pin an output, driving it high.
+  pub fn into_output_high(self) -> Pin<N, Output> {
+    super::pin_output_set(N as u32);
+    self.into_function(PinFunction::OUTPUT)
+  }
+
+  pub fn into_alt0(self) -> Pin<N, Alternate<0>> {
+    self.into_function(PinFunction::ALT0)
+  }
+
+  pub fn into_alt1(self) -> Pin<N, Alternate<1>> {
+    self.into_function(PinFunction::ALT1)
+  }
+
+  pub fn into_alt2(self) -> Pin<N, Alternate<2>> {
+    self.into_function(PinFunction::ALT2)
+  }
+
+  pub fn into_alt3(self) -> Pin<N, Alternate<3>> {
+    self.into_function(PinFunction::ALT3)
+  }
+
+  pub fn into_alt4(self) -> Pin<N, Alternate<4>> {
+    self.into_function(PinFunction::ALT4)
+  }
+
+  pub fn into_alt5(self) -> Pin<N, Alternate<5>> {
+    self.into_function(PinFunction::ALT5)
+  }
+
+  fn into_input_with<PULL>(self, pull: Pull) -> Pin<N, Input<PULL>> {
+    // Before the pull changes, so the edge it may cause doesn't reach the old handler.
+    irq::unregister(N as u32);
+    super::pin_pull_set(N as u32, pull);
+    self.into_function(PinFunction::INPUT)
+  }
+
+  fn into_function<NEW>(self, function: PinFunction) -> Pin<N, NEW> {
+    // A handler registered for the old mode shouldn't see edges caused by the change.
+    irq::unregister(N as u32);
+    super::pin_function_set(N as u32, function);
+    Pin::new()
+  }
+}
+
+impl<const N: u8, PULL> Pin<N, Input<PULL>> {
+  pub fn is_high(&self) -> bool {
+    super::pin_level(N as u32)
+  }
+
+  pub fn is_low(&self) -> bool {
+    !self.is_high()
+  }
+
+  /// Calls `handler` when the pin sees `trigger`, see [irq::register]. Changing the pin's mode stops it.
+  pub fn listen(&mut self, trigger: Trigger, debounce_ms: u32, handler: PinHandler) {
+    irq::register(N as u32, trigger, debounce_ms, handler);
+  }
+
+  pub fn unlisten(&mut self) {
+    irq::unregister(N as u32);
+  }
+}
+
+impl<const N: u8> Pin<N, Output> {
+  pub fn set_high(&mut self) {
+    super::pin_output_set(N as u32);
+  }
+
+  pub fn set_low(&mut self) {
+    super::pin_output_clear(N as u32);
+  }
+
+  /// Whether the pin is driven high, read back from the pin itself.
+  pub fn is_set_high(&self) -> bool {
+    super::pin_level(N as u32)
+  }
+
+  pub fn toggle(&mut self) {
+    if Pin::is_set_high(self) { self.set_low() } else { self.set_high() }
+  }
+}
+
+impl<const N: u8, MODE> ErrorType for Pin<N, MODE> {
+  type Error = Infallible;
+}
+
+impl<const N: u8, PULL> InputPin for Pin<N, Input<PULL>> {
+  fn is_high(&mut self) -> Result<bool, Infallible> {
+    Ok(Pin::is_high(self))
+  }
+
+  fn is_low(&mut self) -> Result<bool, Infallible> {
+    Ok(Pin::is_low(self))
+  }
+}
+
+impl<const N: u8> OutputPin for Pin<N, Output> {
+  fn set_low(&mut self) -> Result<(), Infallible> {
+    Pin::set_low(self);
+    Ok(())
+  }
+
+  fn set_high(&mut self) -> Result<(), Infallible> {
+    Pin::set_high(self);
+    Ok(())
+  }
+}
+
+impl<const N: u8> StatefulOutputPin for Pin<N, Output> {
+  fn is_set_high(&mut self) -> Result<bool, Infallible> {
+    Ok(Pin::is_set_high(self))
+  }
+
+  fn is_set_low(&mut self) -> Result<bool, Infallible> {
+    Ok(!Pin::is_set_high(self))
+  }
+}
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+macro_rules! pins {
+  ($($field:ident: $pin:literal),* $(,)?) => {
+    /// Every GPIO pin, except the ones the kernel drives itself: 14 and 15 (UART TXD/RXD), 16 and 17 (UART CTS/RTS,
+    /// when flow control is on) and 47 (the ACT LED, blinked on panic).
+    pub struct Pins {
+      $(pub $field: Pin<$pin, Unknown>,)*
+    }
+
+    impl Pins {
+      /// The pins, the first time this is called.
+      pub fn take() -> Option<Self> {
+        if TAKEN.swap(true, Ordering::Relaxed) {
+          return None;
+        }
+        Some(Self { $($field: Pin::new(),)* })
+      }
+    }
+  };
+}
+
+pins! {
+  p0: 0,
+  p1: 1,
+  p2: 2,
+  p3: 3,
+  p4: 4,
+  p5: 5,
+  p6: 6,
+  p7: 7,
+  p8: 8,
+  p9: 9,
+  p10: 10,
+  p11: 11,
+  p12: 12,
+  p13: 13,
+  p18: 18,
+  p19: 19,
+  p20: 20,
+  p21: 21,
+  p22: 22,
+  p23: 23,
+  p24: 24,
+  p25: 25,
+  p26: 26,
+  p27: 27,
+  p28: 28,
+  p29: 29,
+  p30: 30,
+  p31: 31,
+  p32: 32,
+  p33: 33,
+  p34: 34,
+  p35: 35,
+  p36: 36,
+  p37: 37,
+  p38: 38,
+  p39: 39,
+  p40: 40,
+  p41: 41,
+  p42: 42,
+  p43: 43,
+  p44: 44,
+  p45: 45,
+  p46: 46,
+  p48: 48,
+  p49: 49,
+  p50: 50,
+  p51: 51,
+  p52: 52,
+  p53: 53,
+}
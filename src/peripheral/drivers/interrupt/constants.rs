@@ -0,0 +1,136 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
+
+use crate::util::mem::{NamedRegister, Register};
+
+const BASE: u32 = 0x7E00B000;
+
+/// IRQ basic pending
+///
+/// Bits 0-7 are the pending state of the ARM specific interrupts (sources 64-71).
+/// Bit 8 is set when one or more bits are set in [IRQ_PENDING_1],
+/// bit 9 is set when one or more bits are set in [IRQ_PENDING_2].
+/// Bits 10-20 are shortcuts for some of the GPU interrupts, which are NOT reflected in bits 8 and 9.
+pub const IRQ_BASIC_PENDING: Register = Register::from_addr(BASE + 0x200);
+/// IRQ pending 1
+///
+/// Pending state of GPU interrupts 0-31.
+pub const IRQ_PENDING_1: Register = Register::from_addr(BASE + 0x204);
+/// IRQ pending 2
+///
+/// Pending state of GPU interrupts 32-63.
+pub const IRQ_PENDING_2: Register = Register::from_addr(BASE + 0x208);
+/// FIQ control
+///
+/// Bits 0-6 select the interrupt source routed to FIQ (see [IrqSource]),
+/// bit 7 enables FIQ generation. Only a single source can be routed to FIQ.
+pub const FIQ_CONTROL: Register = Register::from_addr(BASE + 0x20C);
+/// Enable IRQs 1
+///
+/// Writing a 1 to a bit enables the corresponding GPU interrupt (0-31),
+/// writing a 0 has no effect.
+pub const IRQ_ENABLE_1: Register = Register::from_addr(BASE + 0x210);
+/// Enable IRQs 2
+///
+/// Writing a 1 to a bit enables the corresponding GPU interrupt (32-63),
+/// writing a 0 has no effect.
+pub const IRQ_ENABLE_2: Register = Register::from_addr(BASE + 0x214);
+/// Enable Basic IRQs
+///
+/// Writing a 1 to a bit enables the corresponding ARM specific interrupt (64-71),
+/// writing a 0 has no effect.
+pub const IRQ_ENABLE_BASIC: Register = Register::from_addr(BASE + 0x218);
+/// Disable IRQs 1
+///
+/// Writing a 1 to a bit disables the corresponding GPU interrupt (0-31),
+/// writing a 0 has no effect.
+pub const IRQ_DISABLE_1: Register = Register::from_addr(BASE + 0x21C);
+/// Disable IRQs 2
+///
+/// Writing a 1 to a bit disables the corresponding GPU interrupt (32-63),
+/// writing a 0 has no effect.
+pub const IRQ_DISABLE_2: Register = Register::from_addr(BASE + 0x220);
+/// Disable Basic IRQs
+///
+/// Writing a 1 to a bit disables the corresponding ARM specific interrupt (64-71),
+/// writing a 0 has no effect.
+pub const IRQ_DISABLE_BASIC: Register = Register::from_addr(BASE + 0x224);
+
+/// FIQ enable bit in [FIQ_CONTROL]
+pub const FIQ_CONTROL_ENABLE: u32 = 1 << 7;
+/// FIQ source field mask in [FIQ_CONTROL]
+pub const FIQ_CONTROL_SOURCE: u32 = 0x7F;
+
+/// Interrupt sources.
+///
+/// The discriminants use the same numbering as the [FIQ_CONTROL] source field:
+/// 0-63 are the GPU peripheral interrupts, 64-71 are the ARM specific interrupts.
+/// Sources not listed here are either used by the GPU, or not documented.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IrqSource {
+  /// System timer compare 1, free for the ARM (compare 0 and 2 are used by the GPU)
+  SystemTimer1 = 1,
+  /// System timer compare 3, free for the ARM (compare 0 and 2 are used by the GPU)
+  SystemTimer3 = 3,
+  /// USB controller
+  Usb = 9,
+  /// Auxiliary peripherals (mini UART, SPI1, SPI2)
+  Aux = 29,
+  /// I2C/SPI slave
+  I2cSpiSlave = 43,
+  /// Pixel valve 0
+  Pwa0 = 45,
+  /// Pixel valve 1
+  Pwa1 = 46,
+  /// Secondary memory interface
+  Smi = 48,
+  /// GPIO bank 0 (pins 0-31)
+  Gpio0 = 49,
+  /// GPIO bank 1 (pins 32-53)
+  Gpio1 = 50,
+  /// GPIO bank 2 (unused on the BCM2835)
+  Gpio2 = 51,
+  /// Any GPIO bank
+  Gpio3 = 52,
+  /// I2C (BSC) masters
+  I2c = 53,
+  /// SPI0 master
+  Spi = 54,
+  /// PCM/I2S
+  Pcm = 55,
+  /// PL011 UART
+  Uart = 57,
+  /// ARM timer
+  ArmTimer = 64,
+  /// ARM mailbox
+  ArmMailbox = 65,
+  /// ARM doorbell 0
+  ArmDoorbell0 = 66,
+  /// ARM doorbell 1
+  ArmDoorbell1 = 67,
+  /// GPU0 halted (or GPU1 halted, if bit 10 of the control register is set)
+  Gpu0Halted = 68,
+  /// GPU1 halted
+  Gpu1Halted = 69,
+  /// Illegal access type 1
+  IllegalAccess1 = 70,
+  /// Illegal access type 0
+  IllegalAccess0 = 71,
+}
+
+impl IrqSource {
+  #[inline]
+  pub fn number(&self) -> u32 {
+    *self as u32
+  }
+}
+
+/// Amount of interrupt source numbers, including the ones not listed in [IrqSource]
+pub const IRQ_SOURCE_COUNT: usize = 72;
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  IRQ_BASIC_PENDING, IRQ_PENDING_1, IRQ_PENDING_2, FIQ_CONTROL, IRQ_ENABLE_1, IRQ_ENABLE_2,
+  IRQ_ENABLE_BASIC, IRQ_DISABLE_1, IRQ_DISABLE_2, IRQ_DISABLE_BASIC,
+];
@@ -0,0 +1,192 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use core::cell::UnsafeCell;
+
+use self::constants::{IrqSource, IRQ_SOURCE_COUNT};
+use crate::exception::{self, ExceptionContext, ExceptionKind};
+use crate::util::cpu;
+use crate::util::mem::Register;
+
+pub mod constants;
+
+/// Called when the interrupt it was registered for is pending.
+/// Handlers run with IRQs masked, and must clear the interrupt in the peripheral that raised it.
+pub type IrqHandler = fn();
+
+struct InterruptState {
+  handlers: [Option<IrqHandler>; IRQ_SOURCE_COUNT],
+  fiq_handler: Option<IrqHandler>,
+  // Shadow of the enable registers, indexed by bank (see [bank_of]).
+  enabled: [u32; 3],
+}
+
+struct InterruptStateCell(UnsafeCell<InterruptState>);
+
+// SAFETY: The state is only accessed with IRQs and FIQs masked, and this is a single core system.
+unsafe impl Sync for InterruptStateCell {}
+
+static STATE: InterruptStateCell = InterruptStateCell(UnsafeCell::new(InterruptState {
+  handlers: [None; IRQ_SOURCE_COUNT],
+  fiq_handler: None,
+  enabled: [0; 3],
+}));
+
+fn with_state<R>(f: impl FnOnce(&mut InterruptState) -> R) -> R {
+  cpu::without_interrupts(|| {
+    // SAFETY: IRQs and FIQs are masked, so nothing else can be accessing the state.
+    f(unsafe { &mut *STATE.0.get() })
+  })
+}
+
+// Returns the bank (0 = GPU 0-31, 1 = GPU 32-63, 2 = ARM basic) and the bit within it.
+#[inline]
+fn bank_of(number: u32) -> (usize, u32) {
+  ((number / 32) as usize, number % 32)
+}
+
+#[inline]
+fn enable_register(bank: usize) -> Register {
+  match bank {
+    0 => constants::IRQ_ENABLE_1,
+    1 => constants::IRQ_ENABLE_2,
+    _ => constants::IRQ_ENABLE_BASIC,
+  }
+}
+
+#[inline]
+fn disable_register(bank: usize) -> Register {
+  match bank {
+    0 => constants::IRQ_DISABLE_1,
+    1 => constants::IRQ_DISABLE_2,
+    _ => constants::IRQ_DISABLE_BASIC,
+  }
+}
+
+/// Disables every interrupt source and installs the IRQ and FIQ exception handlers.
+/// IRQs still have to be unmasked on the CPU with [cpu::irq_enable].
+pub fn init() {
+  with_state(|state| {
+    cpu::data_memory_barrier();
+    constants::FIQ_CONTROL.write(0);
+    for bank in 0..3 {
+      disable_register(bank).write(0xFFFF_FFFF);
+    }
+    cpu::data_memory_barrier();
+    state.enabled = [0; 3];
+    state.fiq_handler = None;
+  });
+
+  exception::register_handler(ExceptionKind::Irq, handle_irq);
+  exception::register_handler(ExceptionKind::Fiq, handle_fiq);
+}
+
+/// Register a handler for an interrupt source, replacing the previous one.
+/// This does not enable the source, see [enable].
+pub fn register_handler(source: IrqSource, handler: IrqHandler) {
+  with_state(|state| state.handlers[source.number() as usize] = Some(handler));
+}
+
+/// Disables the interrupt source and removes its handler.
+pub fn unregister_handler(source: IrqSource) {
+  disable(source);
+  with_state(|state| state.handlers[source.number() as usize] = None);
+}
+
+pub fn enable(source: IrqSource) {
+  let (bank, bit) = bank_of(source.number());
+  with_state(|state| {
+    state.enabled[bank] |= 1 << bit;
+    cpu::data_memory_barrier();
+    enable_register(bank).write(1 << bit);
+    cpu::data_memory_barrier();
+  });
+}
+
+pub fn disable(source: IrqSource) {
+  disable_number(source.number());
+}
+
+fn disable_number(number: u32) {
+  let (bank, bit) = bank_of(number);
+  with_state(|state| {
+    state.enabled[bank] &= !(1 << bit);
+    cpu::data_memory_barrier();
+    disable_register(bank).write(1 << bit);
+    cpu::data_memory_barrier();
+  });
+}
+
+pub fn is_enabled(source: IrqSource) -> bool {
+  let (bank, bit) = bank_of(source.number());
+  with_state(|state| state.enabled[bank] & (1 << bit) != 0)
+}
+
+pub fn is_pending(source: IrqSource) -> bool {
+  let (bank, bit) = bank_of(source.number());
+  let register = match bank {
+    0 => constants::IRQ_PENDING_1,
+    1 => constants::IRQ_PENDING_2,
+    _ => constants::IRQ_BASIC_PENDING,
+  };
+  register.read_bit(bit)
+}
+
+/// Route an interrupt source to FIQ instead of IRQ, replacing the previously routed source.
+/// The source is disabled as an IRQ. FIQs still have to be unmasked on the CPU with [cpu::fiq_enable].
+pub fn route_to_fiq(source: IrqSource, handler: IrqHandler) {
+  disable(source);
+  with_state(|state| {
+    state.fiq_handler = Some(handler);
+    cpu::data_memory_barrier();
+    constants::FIQ_CONTROL.write(constants::FIQ_CONTROL_ENABLE | (source.number() & constants::FIQ_CONTROL_SOURCE));
+    cpu::data_memory_barrier();
+  });
+}
+
+/// Stop routing any source to FIQ.
+pub fn disable_fiq() {
+  with_state(|state| {
+    cpu::data_memory_barrier();
+    constants::FIQ_CONTROL.write(0);
+    cpu::data_memory_barrier();
+    state.fiq_handler = None;
+  });
+}
+
+/// Calls the handlers of every pending IRQ. Installed as the IRQ exception handler by [init],
+/// code that takes over the exception handler has to call it, see [crate::thread].
+pub fn handle_irq(_context: &mut ExceptionContext) {
+  cpu::data_memory_barrier();
+  let pending = [
+    constants::IRQ_PENDING_1.read(),
+    constants::IRQ_PENDING_2.read(),
+    constants::IRQ_BASIC_PENDING.read() & 0xFF,
+  ];
+  let enabled = with_state(|state| state.enabled);
+
+  for bank in 0..3 {
+    let mut bits = pending[bank] & enabled[bank];
+    while bits != 0 {
+      let bit = bits.trailing_zeros();
+      bits &= bits - 1;
+      let number = bank as u32 * 32 + bit;
+
+      match with_state(|state| state.handlers[number as usize]) {
+        Some(handler) => handler(),
+        // Nobody is going to clear this interrupt, disable it so we don't get stuck handling it.
+        None => disable_number(number),
+      }
+    }
+  }
+  cpu::data_memory_barrier();
+}
+
+fn handle_fiq(_context: &mut ExceptionContext) {
+  cpu::data_memory_barrier();
+  match with_state(|state| state.fiq_handler) {
+    Some(handler) => handler(),
+    None => constants::FIQ_CONTROL.write(0),
+  }
+  cpu::data_memory_barrier();
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
+
+use crate::util::mem::{NamedRegister, Register};
+
+// The mailbox is not documented in the BCM2835 ARM Peripherals manual.
+// See: https://github.com/raspberrypi/firmware/wiki/Mailboxes
+// and: https://github.com/raspberrypi/firmware/wiki/Mailbox-property-interface
+
+pub const BASE: u32 = 0x7E00B880;
+
+/// Mailbox 0 read (VideoCore to ARM)
+///
+/// Bits 0-3 are the channel, bits 4-31 the data. Reading pops the message off the mailbox.
+pub const MBOX0_READ: Register = Register::from_addr(BASE);
+/// Mailbox 0 peek, reads the next message without popping it
+pub const MBOX0_PEEK: Register = Register::from_addr(BASE + 0x10);
+/// Mailbox 0 sender
+pub const MBOX0_SENDER: Register = Register::from_addr(BASE + 0x14);
+/// Mailbox 0 status, see [bits::STATUS_FULL] and [bits::STATUS_EMPTY]
+pub const MBOX0_STATUS: Register = Register::from_addr(BASE + 0x18);
+/// Mailbox 0 configuration, see [bits::CONFIG_DATA_IRQ]
+pub const MBOX0_CONFIG: Register = Register::from_addr(BASE + 0x1C);
+/// Mailbox 1 write (ARM to VideoCore)
+///
+/// Same format as [MBOX0_READ]. Writing pushes a message.
+pub const MBOX1_WRITE: Register = Register::from_addr(BASE + 0x20);
+/// Mailbox 1 status, see [bits::STATUS_FULL] and [bits::STATUS_EMPTY]
+pub const MBOX1_STATUS: Register = Register::from_addr(BASE + 0x38);
+
+/// Added to an ARM physical address to get the address the VideoCore sees it at.
+/// This is the L2 cache coherent alias of SDRAM, which the firmware uses on the BCM2835.
+pub const BUS_ADDRESS_OFFSET: u32 = 0x4000_0000;
+
+/// Mask of the channel field in a mailbox message
+pub const MESSAGE_CHANNEL: u32 = 0xF;
+/// Mask of the data field in a mailbox message, buffers passed through it must be 16 byte aligned
+pub const MESSAGE_DATA: u32 = !MESSAGE_CHANNEL;
+
+/// Mailbox channels.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Channel {
+  PowerManagement = 0,
+  Framebuffer = 1,
+  VirtualUart = 2,
+  Vchiq = 3,
+  Leds = 4,
+  Buttons = 5,
+  TouchScreen = 6,
+  /// Property tags, ARM to VideoCore
+  Property = 8,
+  /// Property tags, VideoCore to ARM
+  PropertyVcToArm = 9,
+}
+
+/// Request code of a property buffer
+pub const PROPERTY_REQUEST: u32 = 0x0000_0000;
+/// Response code of a property buffer the firmware processed
+pub const PROPERTY_RESPONSE_SUCCESS: u32 = 0x8000_0000;
+/// Response code of a property buffer the firmware couldn't parse
+pub const PROPERTY_RESPONSE_ERROR: u32 = 0x8000_0001;
+/// Set in a tag's request/response code once the firmware has answered it,
+/// the rest of the code is the length of the response in bytes.
+pub const TAG_RESPONSE: u32 = 1 << 31;
+/// Tag marking the end of a property buffer
+pub const TAG_END: u32 = 0;
+
+/// Property tag identifiers.
+pub mod tags {
+  // VideoCore
+  pub const GET_FIRMWARE_REVISION: u32 = 0x0000_0001;
+
+  // Hardware
+  pub const GET_BOARD_MODEL: u32 = 0x0001_0001;
+  pub const GET_BOARD_REVISION: u32 = 0x0001_0002;
+  pub const GET_BOARD_MAC_ADDRESS: u32 = 0x0001_0003;
+  pub const GET_BOARD_SERIAL: u32 = 0x0001_0004;
+  pub const GET_ARM_MEMORY: u32 = 0x0001_0005;
+  pub const GET_VC_MEMORY: u32 = 0x0001_0006;
+  pub const GET_CLOCKS: u32 = 0x0001_0007;
+
+  // Config
+  pub const GET_COMMAND_LINE: u32 = 0x0005_0001;
+
+  // Shared resource management
+  pub const GET_DMA_CHANNELS: u32 = 0x0006_0001;
+
+  // Power
+  pub const GET_POWER_STATE: u32 = 0x0002_0001;
+  pub const GET_TIMING: u32 = 0x0002_0002;
+  pub const SET_POWER_STATE: u32 = 0x0002_8001;
+
+  // Clocks
+  pub const GET_CLOCK_STATE: u32 = 0x0003_0001;
+  pub const SET_CLOCK_STATE: u32 = 0x0003_8001;
+  pub const GET_CLOCK_RATE: u32 = 0x0003_0002;
+  pub const SET_CLOCK_RATE: u32 = 0x0003_8002;
+  pub const GET_MAX_CLOCK_RATE: u32 = 0x0003_0004;
+  pub const GET_MIN_CLOCK_RATE: u32 = 0x0003_0007;
+  pub const GET_TURBO: u32 = 0x0003_0009;
+  pub const SET_TURBO: u32 = 0x0003_8009;
+
+  // Voltage and temperature
+  pub const GET_VOLTAGE: u32 = 0x0003_0003;
+  pub const GET_TEMPERATURE: u32 = 0x0003_0006;
+  pub const GET_MAX_TEMPERATURE: u32 = 0x0003_000A;
+
+  // Framebuffer
+  pub const ALLOCATE_BUFFER: u32 = 0x0004_0001;
+  pub const RELEASE_BUFFER: u32 = 0x0004_8001;
+  pub const BLANK_SCREEN: u32 = 0x0004_0002;
+  pub const GET_PHYSICAL_SIZE: u32 = 0x0004_0003;
+  pub const TEST_PHYSICAL_SIZE: u32 = 0x0004_4003;
+  pub const SET_PHYSICAL_SIZE: u32 = 0x0004_8003;
+  pub const GET_VIRTUAL_SIZE: u32 = 0x0004_0004;
+  pub const TEST_VIRTUAL_SIZE: u32 = 0x0004_4004;
+  pub const SET_VIRTUAL_SIZE: u32 = 0x0004_8004;
+  pub const GET_DEPTH: u32 = 0x0004_0005;
+  pub const SET_DEPTH: u32 = 0x0004_8005;
+  pub const GET_PIXEL_ORDER: u32 = 0x0004_0006;
+  pub const SET_PIXEL_ORDER: u32 = 0x0004_8006;
+  pub const GET_ALPHA_MODE: u32 = 0x0004_0007;
+  pub const SET_ALPHA_MODE: u32 = 0x0004_8007;
+  pub const GET_PITCH: u32 = 0x0004_0008;
+  pub const GET_VIRTUAL_OFFSET: u32 = 0x0004_0009;
+  pub const SET_VIRTUAL_OFFSET: u32 = 0x0004_8009;
+  pub const GET_OVERSCAN: u32 = 0x0004_000A;
+  pub const SET_OVERSCAN: u32 = 0x0004_800A;
+  pub const GET_PALETTE: u32 = 0x0004_000B;
+  pub const SET_PALETTE: u32 = 0x0004_800B;
+}
+
+/// Clock identifiers used by the clock property tags.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Clock {
+  Emmc = 1,
+  Uart = 2,
+  Arm = 3,
+  Core = 4,
+  V3d = 5,
+  H264 = 6,
+  Isp = 7,
+  Sdram = 8,
+  Pixel = 9,
+  Pwm = 10,
+}
+
+/// Device identifiers used by the power property tags.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerDevice {
+  SdCard = 0,
+  Uart0 = 1,
+  Uart1 = 2,
+  UsbHcd = 3,
+  I2c0 = 4,
+  I2c1 = 5,
+  I2c2 = 6,
+  Spi = 7,
+  Ccp2tx = 8,
+}
+
+/// Voltage identifiers used by [tags::GET_VOLTAGE].
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Voltage {
+  Core = 1,
+  SdramC = 2,
+  SdramP = 3,
+  SdramI = 4,
+}
+
+// These are for reference only, to avoid magic numbers in the code.
+// They should not be used anywhere else, so we use pub(in super) to limit their visibility.
+pub(in super) mod bits {
+  /// Set in a status register when the mailbox can't take another message
+  pub const STATUS_FULL: u32 = 31;
+  /// Set in a status register when there are no messages to read
+  pub const STATUS_EMPTY: u32 = 30;
+  /// Raise the mailbox interrupt while mailbox 0 has messages, in [super::MBOX0_CONFIG]
+  pub const CONFIG_DATA_IRQ: u32 = 0;
+
+  /// Power state tags: set if the device is on
+  pub const POWER_ON: u32 = 0;
+  /// Set power state request: wait for the device to become stable before answering
+  pub const POWER_WAIT: u32 = 1;
+  /// Power state responses: set if the device doesn't exist
+  pub const POWER_NO_DEVICE: u32 = 1;
+}
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  MBOX0_READ, MBOX0_PEEK, MBOX0_SENDER, MBOX0_STATUS, MBOX0_CONFIG, MBOX1_WRITE,
+  MBOX1_STATUS,
+];
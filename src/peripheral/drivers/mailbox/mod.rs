@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use self::constants::{bits, tags, Channel, Clock, PowerDevice};
+use crate::peripheral::drivers::interrupt::{self, constants::IrqSource};
+use crate::peripheral::drivers::timer::timer_counter_lower;
+use crate::sync::Mutex;
+use crate::util::cpu;
+
+pub mod constants;
+pub mod property;
+
+pub use self::property::{property_call, PropertyMessage, TagHandle};
+
+/// How long to wait for the firmware to answer, in microseconds.
+const RESPONSE_TIMEOUT_MICROS: u32 = 1_000_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MailboxError {
+  /// The tags don't fit in a property buffer
+  BufferFull,
+  /// The firmware couldn't parse the request
+  RequestFailed,
+  /// The firmware answered with something that isn't a response to our request
+  UnexpectedResponse,
+  /// The firmware didn't answer this tag, it's likely not supported
+  TagNotHandled(u32),
+  /// The response to this tag needed more room than was reserved for it
+  TagTruncated { tag: u32, needed: usize },
+  /// The response to this tag had fewer values than expected
+  ShortResponse(u32),
+  /// No answer arrived within [RESPONSE_TIMEOUT_MICROS]
+  Timeout,
+}
+
+static INTERRUPT_DRIVEN: AtomicBool = AtomicBool::new(false);
+// Messages the interrupt handler has taken off the mailbox, per channel, and a bit per channel that has one waiting.
+static RECEIVED: [AtomicU32; 16] = [const { AtomicU32::new(0) }; 16];
+static RECEIVED_PENDING: AtomicU32 = AtomicU32::new(0);
+// Held for the whole of a [mailbox_call], so answers can't go to the wrong caller.
+static CALL_LOCK: Mutex<()> = Mutex::new(());
+
+#[inline(always)]
+pub fn mailbox_full() -> bool {
+  constants::MBOX1_STATUS.read_bit(bits::STATUS_FULL)
+}
+
+#[inline(always)]
+pub fn mailbox_empty() -> bool {
+  constants::MBOX0_STATUS.read_bit(bits::STATUS_EMPTY)
+}
+
+/// Sends `data` to the VideoCore on `channel`. The low 4 bits of `data` are dropped.
+pub fn mailbox_write(channel: Channel, data: u32) {
+  while mailbox_full() {
+    core::hint::spin_loop();
+  }
+  // Make sure everything written to a buffer passed in `data` is visible before the VideoCore looks at it.
+  cpu::data_memory_barrier();
+  constants::MBOX1_WRITE.write((data & constants::MESSAGE_DATA) | channel as u32);
+}
+
+/// Waits for a message on `channel` and returns its data (with the channel bits cleared).
+/// Messages for other channels that arrive in the meantime are dropped.
+pub fn mailbox_read(channel: Channel) -> Result<u32, MailboxError> {
+  let start = timer_counter_lower();
+  loop {
+    if let Some(data) = try_receive(channel) {
+      // Make sure nothing in a response buffer is read before the VideoCore is done with it.
+      cpu::data_memory_barrier();
+      return Ok(data);
+    }
+    if timer_counter_lower().wrapping_sub(start) >= RESPONSE_TIMEOUT_MICROS {
+      return Err(MailboxError::Timeout);
+    }
+    if INTERRUPT_DRIVEN.load(Ordering::Relaxed) && cpu::irqs_enabled() {
+      cpu::irq_disable();
+      if RECEIVED_PENDING.load(Ordering::Acquire) & (1 << channel as u32) == 0 {
+        // Returns once an interrupt is pending, even though it's masked.
+        cpu::wait_for_interrupt();
+      }
+      cpu::irq_enable();
+    } else {
+      core::hint::spin_loop();
+    }
+  }
+}
+
+// Takes a message for `channel` from whichever of the interrupt handler and the mailbox has one.
+// Messages for other channels found in the mailbox are dropped.
+fn try_receive(channel: Channel) -> Option<u32> {
+  let bit = 1 << channel as u32;
+  cpu::without_irqs(|| {
+    if RECEIVED_PENDING.load(Ordering::Acquire) & bit != 0 {
+      RECEIVED_PENDING.fetch_and(!bit, Ordering::AcqRel);
+      return Some(RECEIVED[channel as usize].load(Ordering::Relaxed));
+    }
+    while !mailbox_empty() {
+      let message = constants::MBOX0_READ.read();
+      if message & constants::MESSAGE_CHANNEL == channel as u32 {
+        return Some(message & constants::MESSAGE_DATA);
+      }
+    }
+    None
+  })
+}
+
+/// Sends `data` on `channel` and waits for the answer. Other threads making a call wait until this one is done.
+///
+/// Note: Waits for a lock, so don't use the mailbox from interrupt handlers.
+pub fn mailbox_call(channel: Channel, data: u32) -> Result<u32, MailboxError> {
+  let _lock = CALL_LOCK.lock();
+  discard_received(channel);
+  mailbox_write(channel, data);
+  mailbox_read(channel)
+}
+
+// Drops an answer to an earlier call on `channel` that timed out, so it isn't mistaken for the answer to the next one.
+// Messages for other channels still in the mailbox are dropped as well.
+fn discard_received(channel: Channel) {
+  cpu::without_irqs(|| {
+    RECEIVED_PENDING.fetch_and(!(1 << channel as u32), Ordering::AcqRel);
+    while !mailbox_empty() {
+      constants::MBOX0_READ.read();
+    }
+  });
+}
+
+/// Wait for answers with the mailbox interrupt instead of spinning.
+/// Requires the interrupt controller to be initialized, see [interrupt::init].
+pub fn mailbox_enable_interrupts() {
+  interrupt::register_handler(IrqSource::ArmMailbox, mailbox_handle_interrupt);
+  cpu::without_irqs(|| {
+    constants::MBOX0_CONFIG.write_bit(bits::CONFIG_DATA_IRQ, 1);
+    INTERRUPT_DRIVEN.store(true, Ordering::Relaxed);
+  });
+  interrupt::enable(IrqSource::ArmMailbox);
+}
+
+pub fn mailbox_disable_interrupts() {
+  interrupt::disable(IrqSource::ArmMailbox);
+  cpu::without_irqs(|| {
+    constants::MBOX0_CONFIG.write_bit(bits::CONFIG_DATA_IRQ, 0);
+    INTERRUPT_DRIVEN.store(false, Ordering::Relaxed);
+  });
+}
+
+// The interrupt stays asserted while mailbox 0 has messages, so empty it.
+fn mailbox_handle_interrupt() {
+  while !mailbox_empty() {
+    let message = constants::MBOX0_READ.read();
+    let channel = message & constants::MESSAGE_CHANNEL;
+    RECEIVED[channel as usize].store(message & constants::MESSAGE_DATA, Ordering::Relaxed);
+    RECEIVED_PENDING.fetch_or(1 << channel, Ordering::Release);
+  }
+}
+
+pub fn firmware_revision() -> Result<u32, MailboxError> {
+  property_call::<1>(tags::GET_FIRMWARE_REVISION, &[]).map(|[revision]| revision)
+}
+
+pub fn board_model() -> Result<u32, MailboxError> {
+  property_call::<1>(tags::GET_BOARD_MODEL, &[]).map(|[model]| model)
+}
+
+/// Board revision code, see https://www.raspberrypi.com/documentation/computers/raspberry-pi.html#raspberry-pi-revision-codes
+pub fn board_revision() -> Result<u32, MailboxError> {
+  property_call::<1>(tags::GET_BOARD_REVISION, &[]).map(|[revision]| revision)
+}
+
+pub fn board_serial() -> Result<u64, MailboxError> {
+  property_call::<2>(tags::GET_BOARD_SERIAL, &[]).map(|[low, high]| ((high as u64) << 32) | low as u64)
+}
+
+/// MAC address of the board's network interface, in network byte order.
+pub fn mac_address() -> Result<[u8; 6], MailboxError> {
+  let [low, high] = property_call::<2>(tags::GET_BOARD_MAC_ADDRESS, &[])?;
+  let (low, high) = (low.to_le_bytes(), high.to_le_bytes());
+  Ok([low[0], low[1], low[2], low[3], high[0], high[1]])
+}
+
+/// Base address and size of the memory the ARM gets.
+pub fn arm_memory() -> Result<(u32, u32), MailboxError> {
+  property_call::<2>(tags::GET_ARM_MEMORY, &[]).map(|[base, size]| (base, size))
+}
+
+/// Base address and size of the memory the VideoCore keeps for itself (the GPU memory split).
+pub fn vc_memory() -> Result<(u32, u32), MailboxError> {
+  property_call::<2>(tags::GET_VC_MEMORY, &[]).map(|[base, size]| (base, size))
+}
+
+/// Current rate of a clock, in Hz.
+pub fn clock_rate(clock: Clock) -> Result<u32, MailboxError> {
+  property_call::<2>(tags::GET_CLOCK_RATE, &[clock as u32]).map(|[_, rate]| rate)
+}
+
+pub fn max_clock_rate(clock: Clock) -> Result<u32, MailboxError> {
+  property_call::<2>(tags::GET_MAX_CLOCK_RATE, &[clock as u32]).map(|[_, rate]| rate)
+}
+
+pub fn min_clock_rate(clock: Clock) -> Result<u32, MailboxError> {
+  property_call::<2>(tags::GET_MIN_CLOCK_RATE, &[clock as u32]).map(|[_, rate]| rate)
+}
+
+/// Sets the rate of a clock in Hz, returning the rate that was actually set.
+pub fn set_clock_rate(clock: Clock, rate: u32) -> Result<u32, MailboxError> {
+  // The third value asks the firmware to not change the turbo settings.
+  property_call::<2>(tags::SET_CLOCK_RATE, &[clock as u32, rate, 0]).map(|[_, rate]| rate)
+}
+
+/// SoC temperature in thousandths of a degree Celsius.
+pub fn temperature() -> Result<u32, MailboxError> {
+  property_call::<2>(tags::GET_TEMPERATURE, &[0]).map(|[_, temperature]| temperature)
+}
+
+/// Temperature at which the firmware starts throttling, in thousandths of a degree Celsius.
+pub fn max_temperature() -> Result<u32, MailboxError> {
+  property_call::<2>(tags::GET_MAX_TEMPERATURE, &[0]).map(|[_, temperature]| temperature)
+}
+
+/// Whether a device is powered on, or `None` if the board doesn't have it.
+pub fn power_state(device: PowerDevice) -> Result<Option<bool>, MailboxError> {
+  let [_, state] = property_call::<2>(tags::GET_POWER_STATE, &[device as u32])?;
+  if state & (1 << bits::POWER_NO_DEVICE) != 0 {
+    return Ok(None);
+  }
+  Ok(Some(state & (1 << bits::POWER_ON) != 0))
+}
+
+/// Powers a device on or off, waiting for it to become stable. Returns the new state, see [power_state].
+pub fn set_power_state(device: PowerDevice, on: bool) -> Result<Option<bool>, MailboxError> {
+  let request = ((on as u32) << bits::POWER_ON) | (1 << bits::POWER_WAIT);
+  let [_, state] = property_call::<2>(tags::SET_POWER_STATE, &[device as u32, request])?;
+  if state & (1 << bits::POWER_NO_DEVICE) != 0 {
+    return Ok(None);
+  }
+  Ok(Some(state & (1 << bits::POWER_ON) != 0))
+}
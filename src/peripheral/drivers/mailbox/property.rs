@@ -0,0 +1,122 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use super::constants::{self, Channel};
+use super::MailboxError;
+
+/// Size of a property message buffer in 32 bit words, including the header and end tag.
+pub const PROPERTY_BUFFER_WORDS: usize = 256;
+
+// Words in the buffer header: total size and request/response code.
+const HEADER_WORDS: usize = 2;
+// Words in a tag header: identifier, value buffer size and request/response code.
+const TAG_HEADER_WORDS: usize = 3;
+
+#[repr(C, align(16))]
+struct PropertyBuffer([u32; PROPERTY_BUFFER_WORDS]);
+
+/// A property channel message, made of one or more tags.
+///
+/// ```ignore
+/// let mut message = PropertyMessage::new();
+/// let revision = message.add_tag(tags::GET_BOARD_REVISION, &[], 1)?;
+/// message.send()?;
+/// let revision = message.response(revision)?[0];
+/// ```
+pub struct PropertyMessage {
+  buffer: PropertyBuffer,
+  // Words used so far, excluding the end tag.
+  len: usize,
+}
+
+/// Refers to a tag added to a [PropertyMessage], to read its response.
+#[derive(Clone, Copy, Debug)]
+pub struct TagHandle {
+  // Word index of the tag header
+  offset: usize,
+}
+
+impl PropertyMessage {
+  pub const fn new() -> Self {
+    Self {
+      buffer: PropertyBuffer([0; PROPERTY_BUFFER_WORDS]),
+      len: HEADER_WORDS,
+    }
+  }
+
+  /// Appends a tag with the `request` values, and room for a `response_words` long response.
+  pub fn add_tag(&mut self, tag: u32, request: &[u32], response_words: usize) -> Result<TagHandle, MailboxError> {
+    let value_words = request.len().max(response_words);
+    // Keep room for the end tag.
+    if self.len + TAG_HEADER_WORDS + value_words + 1 > PROPERTY_BUFFER_WORDS {
+      return Err(MailboxError::BufferFull);
+    }
+
+    let offset = self.len;
+    let words = &mut self.buffer.0;
+    words[offset] = tag;
+    words[offset + 1] = (value_words * 4) as u32;
+    words[offset + 2] = constants::PROPERTY_REQUEST;
+    let values = &mut words[offset + TAG_HEADER_WORDS..offset + TAG_HEADER_WORDS + value_words];
+    values.fill(0);
+    values[..request.len()].copy_from_slice(request);
+
+    self.len += TAG_HEADER_WORDS + value_words;
+    Ok(TagHandle { offset })
+  }
+
+  /// Sends the message on the property channel and waits for the firmware to answer it.
+  pub fn send(&mut self) -> Result<(), MailboxError> {
+    let words = &mut self.buffer.0;
+    words[self.len] = constants::TAG_END;
+    words[0] = ((self.len + 1) * 4) as u32;
+    words[1] = constants::PROPERTY_REQUEST;
+
+    let address = words.as_ptr() as u32;
+    let response = super::mailbox_call(Channel::Property, address + constants::BUS_ADDRESS_OFFSET)?;
+    if response & constants::MESSAGE_DATA != (address + constants::BUS_ADDRESS_OFFSET) & constants::MESSAGE_DATA {
+      return Err(MailboxError::UnexpectedResponse);
+    }
+
+    // SAFETY: The firmware wrote to the buffer behind the compiler's back.
+    match unsafe { core::ptr::read_volatile(&self.buffer.0[1]) } {
+      constants::PROPERTY_RESPONSE_SUCCESS => Ok(()),
+      constants::PROPERTY_RESPONSE_ERROR => Err(MailboxError::RequestFailed),
+      _ => Err(MailboxError::UnexpectedResponse),
+    }
+  }
+
+  /// The response values of a tag, after the message was sent.
+  /// Fails if the firmware didn't answer the tag, or its answer didn't fit the space reserved for it.
+  pub fn response(&self, handle: TagHandle) -> Result<&[u32], MailboxError> {
+    let words = &self.buffer.0;
+    let tag = words[handle.offset];
+    let value_bytes = words[handle.offset + 1] as usize;
+    // SAFETY: The firmware wrote to the buffer behind the compiler's back.
+    let code = unsafe { core::ptr::read_volatile(&words[handle.offset + 2]) };
+    if code & constants::TAG_RESPONSE == 0 {
+      return Err(MailboxError::TagNotHandled(tag));
+    }
+    let response_bytes = (code & !constants::TAG_RESPONSE) as usize;
+    if response_bytes > value_bytes {
+      return Err(MailboxError::TagTruncated { tag, needed: response_bytes });
+    }
+    let start = handle.offset + TAG_HEADER_WORDS;
+    Ok(&words[start..start + response_bytes.div_ceil(4)])
+  }
+}
+
+/// Sends a single tag and returns its response values.
+/// This is a shortcut for messages with just one tag, see [PropertyMessage] for anything more.
+pub fn property_call<const N: usize>(tag: u32, request: &[u32]) -> Result<[u32; N], MailboxError> {
+  let mut message = PropertyMessage::new();
+  let handle = message.add_tag(tag, request, N)?;
+  message.send()?;
+  let response = message.response(handle)?;
+  if response.len() < N {
+    return Err(MailboxError::ShortResponse(tag));
+  }
+  let mut values = [0; N];
+  values.copy_from_slice(&response[..N]);
+  Ok(values)
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
+
+use crate::util::mem::{NamedRegister, Register};
+
+const BASE: u32 = 0x7E215000;
+
+/// Auxiliary Interrupt status
+///
+/// Bit 0 is set when the mini UART has an interrupt pending,
+/// bits 1 and 2 are the same for SPI1 and SPI2.
+pub const AUX_IRQ: Register = Register::from_addr(BASE);
+/// Auxiliary enables
+///
+/// Bit 0 enables the mini UART, bits 1 and 2 enable SPI1 and SPI2.
+/// While a module is disabled, its registers can't be accessed.
+pub const AUX_ENABLES: Register = Register::from_addr(BASE + 0x04);
+/// Mini UART I/O Data
+///
+/// Writing pushes a byte onto the transmit FIFO, reading pops a byte from the receive FIFO.
+/// If the DLAB bit in [AUX_MU_LCR] is set, this is the lower 8 bits of the baud rate register instead.
+pub const AUX_MU_IO: Register = Register::from_addr(BASE + 0x40);
+/// Mini UART Interrupt Enable
+///
+/// Bit 0 enables the receive interrupt, bit 1 enables the transmit interrupt.
+/// If the DLAB bit in [AUX_MU_LCR] is set, this is the upper 8 bits of the baud rate register instead.
+pub const AUX_MU_IER: Register = Register::from_addr(BASE + 0x44);
+/// Mini UART Interrupt Identify
+///
+/// On read, bits 2:1 identify the pending interrupt. On write,
+/// bit 1 clears the receive FIFO and bit 2 clears the transmit FIFO.
+pub const AUX_MU_IIR: Register = Register::from_addr(BASE + 0x48);
+/// Mini UART Line Control
+///
+/// Bits 1:0 select the data size (0b00 = 7 bits, 0b11 = 8 bits),
+/// bit 6 sends a break, bit 7 (DLAB) gives access to the baud rate register through [AUX_MU_IO] and [AUX_MU_IER].
+pub const AUX_MU_LCR: Register = Register::from_addr(BASE + 0x4C);
+/// Mini UART Modem Control
+///
+/// Bit 1 controls the RTS line, if it isn't under automatic flow control.
+pub const AUX_MU_MCR: Register = Register::from_addr(BASE + 0x50);
+/// Mini UART Line Status
+///
+/// Bit 0 is set when the receive FIFO holds at least one byte, bit 1 on receiver overrun (cleared on read),
+/// bit 5 when the transmit FIFO can accept at least one byte, bit 6 when the transmitter is idle.
+pub const AUX_MU_LSR: Register = Register::from_addr(BASE + 0x54);
+/// Mini UART Modem Status
+///
+/// Bit 5 is the inverted CTS line.
+pub const AUX_MU_MSR: Register = Register::from_addr(BASE + 0x58);
+/// Mini UART Scratch
+///
+/// A single byte of storage, not used by the hardware.
+pub const AUX_MU_SCRATCH: Register = Register::from_addr(BASE + 0x5C);
+/// Mini UART Extra Control
+///
+/// Bit 0 enables the receiver, bit 1 enables the transmitter.
+/// The remaining bits configure automatic RTS/CTS flow control.
+pub const AUX_MU_CNTL: Register = Register::from_addr(BASE + 0x60);
+/// Mini UART Extra Status
+///
+/// Gives the internal state of the mini UART, most notably the FIFO fill levels
+/// (bits 19:16 for the receive FIFO, bits 27:24 for the transmit FIFO).
+pub const AUX_MU_STAT: Register = Register::from_addr(BASE + 0x64);
+/// Mini UART Baudrate
+///
+/// 16 bit baud rate counter, baudrate = system_clock / (8 * (value + 1)).
+pub const AUX_MU_BAUD: Register = Register::from_addr(BASE + 0x68);
+
+/// The mini UART's baud rate is derived from the VPU core clock. It differs between boards (the Pi Zero's is 400 MHz)
+/// and with `core_freq` in config.txt, so it should be asked from the firmware. This is the usual default otherwise.
+pub const SYSTEM_CLOCK_HZ: u32 = 250_000_000;
+
+/// Depth of both the receive and the transmit FIFO
+pub const FIFO_DEPTH: u32 = 8;
+
+// These are for reference only, to avoid magic numbers in the code.
+// They should not be used anywhere else, so we use pub(in super) to limit their visibility.
+pub(in super) mod bits {
+  /// Mini UART enable, in AUX_ENABLES
+  pub const ENABLES_MINI_UART: u32 = 0;
+
+  /// Clear receive FIFO, in AUX_MU_IIR (write)
+  pub const IIR_CLEAR_RX: u32 = 1;
+  /// Clear transmit FIFO, in AUX_MU_IIR (write)
+  pub const IIR_CLEAR_TX: u32 = 2;
+
+  /// 8 bit data size, in AUX_MU_LCR.
+  /// The datasheet only documents bit 0, but both bits have to be set for 8 bit mode.
+  pub const LCR_8_BIT: u32 = 0b11;
+
+  /// Data ready, in AUX_MU_LSR
+  pub const LSR_DATA_READY: u32 = 0;
+  /// Receiver overrun, in AUX_MU_LSR
+  pub const LSR_RX_OVERRUN: u32 = 1;
+  /// Transmitter empty (can accept at least one byte), in AUX_MU_LSR
+  pub const LSR_TX_EMPTY: u32 = 5;
+  /// Transmitter idle, in AUX_MU_LSR
+  pub const LSR_TX_IDLE: u32 = 6;
+
+  /// Receiver enable, in AUX_MU_CNTL
+  pub const CNTL_RX_ENABLE: u32 = 0;
+  /// Transmitter enable, in AUX_MU_CNTL
+  pub const CNTL_TX_ENABLE: u32 = 1;
+
+  /// Transmit FIFO is full, in AUX_MU_STAT
+  pub const STAT_TX_FULL: u32 = 5;
+  /// Shift of the receive FIFO fill level (4 bits), in AUX_MU_STAT
+  pub const STAT_RX_LEVEL_SHIFT: u32 = 16;
+  /// Shift of the transmit FIFO fill level (4 bits), in AUX_MU_STAT
+  pub const STAT_TX_LEVEL_SHIFT: u32 = 24;
+}
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  AUX_IRQ, AUX_ENABLES, AUX_MU_IO, AUX_MU_IER, AUX_MU_IIR, AUX_MU_LCR,
+  AUX_MU_MCR, AUX_MU_LSR, AUX_MU_MSR, AUX_MU_SCRATCH, AUX_MU_CNTL, AUX_MU_STAT,
+  AUX_MU_BAUD,
+];
@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use self::constants::bits;
+use crate::peripheral::drivers::gpio::{self, constants::PinFunction};
+
+pub mod constants;
+
+static OVERRUN_ERRORS: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MiniUartConfigError {
+  /// The baud rate can't be derived from the system clock
+  UnsupportedBaudRate,
+}
+
+/// Enables the mini UART (AUX UART1) in 8 bit mode at `baud_rate`, given a system clock of `clock_hz`
+/// (see [constants::SYSTEM_CLOCK_HZ]), and routes GPIO 14 and 15 to it.
+///
+/// This takes the pins away from the PL011 UART, only one of them can be on GPIO 14/15 at a time.
+/// Nothing is changed if the baud rate can't be derived from the system clock.
+pub fn mini_uart_init(clock_hz: u32, baud_rate: u32) -> Result<(), MiniUartConfigError> {
+  let divisor = mini_uart_baud_divisor(clock_hz, baud_rate)?;
+
+  // TXD1 and RXD1
+  gpio::pin_function_set(14, PinFunction::ALT5);
+  gpio::pin_function_set(15, PinFunction::ALT5);
+
+  constants::AUX_ENABLES.write_bit(bits::ENABLES_MINI_UART, 1);
+  // Disable the receiver and transmitter while configuring
+  constants::AUX_MU_CNTL.write(0);
+  // No interrupts
+  constants::AUX_MU_IER.write(0);
+  constants::AUX_MU_LCR.write(bits::LCR_8_BIT);
+  // RTS high, no flow control
+  constants::AUX_MU_MCR.write(0);
+  constants::AUX_MU_IIR.write((1 << bits::IIR_CLEAR_RX) | (1 << bits::IIR_CLEAR_TX));
+  constants::AUX_MU_BAUD.write(divisor);
+  constants::AUX_MU_CNTL.write((1 << bits::CNTL_RX_ENABLE) | (1 << bits::CNTL_TX_ENABLE));
+  Ok(())
+}
+
+/// Sets the baud rate, derived from a system clock of `clock_hz` (see [constants::SYSTEM_CLOCK_HZ]).
+/// The closest achievable rate is used.
+pub fn mini_uart_set_baud_rate(clock_hz: u32, baud_rate: u32) -> Result<(), MiniUartConfigError> {
+  constants::AUX_MU_BAUD.write(mini_uart_baud_divisor(clock_hz, baud_rate)?);
+  Ok(())
+}
+
+/// The AUX_MU_BAUD value for `baud_rate`, given a system clock of `clock_hz`.
+pub fn mini_uart_baud_divisor(clock_hz: u32, baud_rate: u32) -> Result<u32, MiniUartConfigError> {
+  if baud_rate == 0 {
+    return Err(MiniUartConfigError::UnsupportedBaudRate);
+  }
+  // baudrate = clock / (8 * (divisor + 1)), rounded to the nearest divisor.
+  let divisor = (clock_hz as u64 + 4 * baud_rate as u64) / (8 * baud_rate as u64);
+  if divisor == 0 || divisor > 0x1_0000 {
+    return Err(MiniUartConfigError::UnsupportedBaudRate);
+  }
+  Ok(divisor as u32 - 1)
+}
+
+#[inline(always)]
+pub fn mini_uart_transmit_fifo_empty() -> bool {
+  (constants::AUX_MU_STAT.read() >> bits::STAT_TX_LEVEL_SHIFT) & 0xF == 0
+}
+
+#[inline(always)]
+pub fn mini_uart_receive_fifo_full() -> bool {
+  (constants::AUX_MU_STAT.read() >> bits::STAT_RX_LEVEL_SHIFT) & 0xF >= constants::FIFO_DEPTH
+}
+
+#[inline(always)]
+pub fn mini_uart_transmit_fifo_full() -> bool {
+  constants::AUX_MU_STAT.read_bit(bits::STAT_TX_FULL)
+}
+
+#[inline(always)]
+pub fn mini_uart_receive_fifo_empty() -> bool {
+  !constants::AUX_MU_LSR.read_bit(bits::LSR_DATA_READY)
+}
+
+#[inline(always)]
+pub fn mini_uart_busy() -> bool {
+  !constants::AUX_MU_LSR.read_bit(bits::LSR_TX_IDLE)
+}
+
+#[inline(always)]
+pub fn mini_uart_read() -> u8 {
+  (constants::AUX_MU_IO.read() & 0xFF) as u8
+}
+
+#[inline(always)]
+pub fn mini_uart_write(data: u8) {
+  constants::AUX_MU_IO.write(data as u32);
+}
+
+#[inline(always)]
+pub fn mini_uart_write_str(s: &str) {
+  for &b in s.as_bytes() {
+    // Send the byte
+    mini_uart_write_byte(b);
+  }
+}
+
+#[inline(always)]
+pub fn mini_uart_write_byte(b: u8) {
+  // Wait until we can send
+  while !constants::AUX_MU_LSR.read_bit(bits::LSR_TX_EMPTY) {}
+  // Send the byte
+  mini_uart_write(b);
+}
+
+/// Reads a received byte if there is one, without blocking.
+pub fn mini_uart_try_read() -> Option<u8> {
+  // Reading LSR clears the overrun flag, so both flags come from the same read.
+  let status = constants::AUX_MU_LSR.read();
+  if status & (1 << bits::LSR_RX_OVERRUN) != 0 {
+    OVERRUN_ERRORS.fetch_add(1, Ordering::Relaxed);
+  }
+  if status & (1 << bits::LSR_DATA_READY) == 0 {
+    return None;
+  }
+  Some(mini_uart_read())
+}
+
+#[inline(always)]
+pub fn mini_uart_read_blocking() -> u8 {
+  // Wait until something is in the buffer
+  loop {
+    if let Some(data) = mini_uart_try_read() {
+      return data;
+    }
+  }
+}
+
+/// Waits until everything in the transmit FIFO has been sent.
+pub fn mini_uart_flush() {
+  while mini_uart_busy() {}
+}
+
+/// Amount of receiver overruns seen since boot.
+/// The mini UART has no framing or parity error detection.
+pub fn mini_uart_overrun_errors() -> u32 {
+  OVERRUN_ERRORS.load(Ordering::Relaxed)
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
 
-use crate::util::mem::Register;
+use crate::util::mem::{NamedRegister, Register};
 
 const BASE: u32 = 0x7E204000;
 
@@ -189,4 +189,9 @@ pub(in super) mod pins {
   /// Chip Select (0, 1, 2)
   /// See CS_CS_START
   pub const CS_CS_END: u32 = 1;
-}
\ No newline at end of file
+}
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  SPI_CS, SPI_FIFO, SPI_CLK, SPI_DLEN, SPI_LTOH, SPI_DC,
+];
@@ -1,7 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
 
-use crate::util::mem::Register;
+use crate::util::mem::{NamedRegister, Register};
 
 const BASE: u32 = 0x7E003000;
 
@@ -20,13 +20,8 @@ pub const TIMER_C2: Register = Register::from_addr(BASE + 0x14);
 /// System Timer Compare 3
 pub const TIMER_C3: Register = Register::from_addr(BASE + 0x18);
 
-// Where did I get these from? These are not in the BCM2835 ARM Peripherals doc.
-// pub const TIMER_LOAD: *mut u32 = apply_mask!(0x7E003000);
-// pub const TIMER_VALUE: *mut u32 = apply_mask!(0x7E003004);
-// pub const TIMER_CONTROL: *mut u32 = apply_mask!(0x7E003008);
-// pub const TIMER_IRQ_CLR: *mut u32 = apply_mask!(0x7E00300C);
-// pub const TIMER_RAW_IRQ: *mut u32 = apply_mask!(0x7E003010);
-// pub const TIMER_MASKED_IRQ: *mut u32 = apply_mask!(0x7E003014);
-// pub const TIMER_RELOAD: *mut u32 = apply_mask!(0x7E003018);
-// pub const TIMER_PREDIVIDER: *mut u32 = apply_mask!(0x7E00301C);
-// pub const TIMER_FREE_RUNNING_COUNTER: *mut u32 = apply_mask!(0x7E003020);
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  TIMER_CS, TIMER_CLO, TIMER_CHI, TIMER_C0, TIMER_C1, TIMER_C2,
+  TIMER_C3,
+];
@@ -1,6 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
 
+use crate::time::counter::combine_counter;
 use crate::util::mem::Register;
 
 pub mod constants;
@@ -37,9 +38,11 @@ impl Timer {
     constants::TIMER_CS.read_bit(self.0 as u32)
   }
 
+  /// Clears the match flag, and with it the interrupt.
   #[inline]
   pub fn clear_interrupt(&self) {
-    constants::TIMER_CS.write_bit(self.0 as u32, 1);
+    // Writing a 1 clears the flag, zeroes leave the other channels' flags alone.
+    constants::TIMER_CS.write(1 << self.0);
   }
 }
 
@@ -53,7 +56,13 @@ pub fn timer_counter_higher() -> u32 {
   constants::TIMER_CHI.read()
 }
 
+/// The full 64 bit counter, in microseconds since power on. See [crate::time::Instant] for using it.
 #[inline]
 pub fn timer_counter() -> u64 {
-  ((timer_counter_higher() as u64) << 32) | timer_counter_lower() as u64
+  // The halves can't be read at once, the high half is read on both sides of the low one to catch the low half wrapping.
+  let high = timer_counter_higher();
+  let low = timer_counter_lower();
+  let high_again = timer_counter_higher();
+  let low_again = timer_counter_lower();
+  combine_counter(high, low, high_again, low_again)
 }
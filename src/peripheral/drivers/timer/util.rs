@@ -1,15 +1,11 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 
-use core::hint::spin_loop;
+use core::time::Duration;
 
-use super::timer_counter_lower;
+use crate::time;
 
-// TODO: Make this interrupt-driven
+/// Busy-waits for at least `nanos` nanoseconds. The system timer counts microseconds, so this rounds up to the next one.
 #[allow(unused, reason = "This function may be unused as it is a utility function")]
 pub fn wait_nanos(nanos: u32) {
-  let target = timer_counter_lower().wrapping_add(nanos);
-  while timer_counter_lower() < target {
-    spin_loop();
-  }
+  time::sleep(Duration::from_nanos(nanos as u64));
 }
- 
\ No newline at end of file
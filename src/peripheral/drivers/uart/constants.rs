@@ -1,7 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
 
-use crate::util::mem::Register;
+use crate::util::mem::{NamedRegister, Register};
 
 pub const BASE: u32 = 0x7E201000;
 
@@ -52,3 +52,89 @@ pub const UART_ITIP: Register = Register::from_addr(BASE + 0x84);
 pub const UART_ITOP: Register = Register::from_addr(BASE + 0x88);
 /// Test Data reg
 pub const UART_TDR: Register = Register::from_addr(BASE + 0x8c);
+
+// These are for reference only, to avoid magic numbers in the code.
+// They should not be used anywhere else, so we use pub(in super) to limit their visibility.
+pub(in super) mod bits {
+  /// Receive interrupt, asserted when the receive FIFO reaches the [IFLS_RX_SHIFT] level.
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_RX: u32 = 4;
+  /// Transmit interrupt, asserted when the transmit FIFO drops to the [IFLS_TX_SHIFT] level.
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_TX: u32 = 5;
+  /// Receive timeout interrupt, asserted when the receive FIFO is not empty
+  /// and no more data has arrived for 32 bit periods.
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_RT: u32 = 6;
+  /// Framing error interrupt
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_FE: u32 = 7;
+  /// Parity error interrupt
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_PE: u32 = 8;
+  /// Break error interrupt
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_BE: u32 = 9;
+  /// Overrun error interrupt
+  /// Bit position in UART_IMSC, UART_RIS, UART_MIS and UART_ICR.
+  pub const INT_OE: u32 = 10;
+  /// Every interrupt bit in UART_ICR
+  pub const INT_ALL: u32 = 0x7FF;
+
+  /// Shift of the transmit interrupt FIFO level select in UART_IFLS (3 bits).
+  pub const IFLS_TX_SHIFT: u32 = 0;
+  /// Shift of the receive interrupt FIFO level select in UART_IFLS (3 bits).
+  pub const IFLS_RX_SHIFT: u32 = 3;
+  // FIFO level select values for UART_IFLS
+  /// 1/8 full
+  pub const IFLS_1_8: u32 = 0b000;
+  /// 1/4 full
+  pub const IFLS_1_4: u32 = 0b001;
+  /// 1/2 full
+  pub const IFLS_1_2: u32 = 0b010;
+  /// 3/4 full
+  pub const IFLS_3_4: u32 = 0b011;
+  /// 7/8 full
+  pub const IFLS_7_8: u32 = 0b100;
+
+  /// Send break, in UART_LCRH
+  pub const LCRH_BRK: u32 = 0;
+  /// Parity enable, in UART_LCRH
+  pub const LCRH_PEN: u32 = 1;
+  /// Even parity select (0 = odd parity), in UART_LCRH
+  pub const LCRH_EPS: u32 = 2;
+  /// Two stop bits select, in UART_LCRH
+  pub const LCRH_STP2: u32 = 3;
+  /// Enable FIFOs, in UART_LCRH
+  pub const LCRH_FEN: u32 = 4;
+  /// Shift of the word length field (2 bits, 0b00 = 5 bits .. 0b11 = 8 bits), in UART_LCRH
+  pub const LCRH_WLEN_SHIFT: u32 = 5;
+  /// Stick parity select, in UART_LCRH
+  pub const LCRH_SPS: u32 = 7;
+
+  /// UART enable, in UART_CR
+  pub const CR_UARTEN: u32 = 0;
+  /// Loopback enable, in UART_CR
+  pub const CR_LBE: u32 = 7;
+  /// Transmit enable, in UART_CR
+  pub const CR_TXE: u32 = 8;
+  /// Receive enable, in UART_CR
+  pub const CR_RXE: u32 = 9;
+  /// Request to send, in UART_CR
+  pub const CR_RTS: u32 = 11;
+  /// RTS hardware flow control enable, in UART_CR
+  pub const CR_RTSEN: u32 = 14;
+  /// CTS hardware flow control enable, in UART_CR
+  pub const CR_CTSEN: u32 = 15;
+}
+
+/// Reference clock of the UART (UARTCLK), set by the firmware.
+/// This is the default of `init_uart_clock` in config.txt, the baud rate divisors are derived from it.
+pub const UART_CLOCK_HZ: u32 = 48_000_000;
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  UART_DR, UART_RSRECR, UART_FR, UART_ILPR, UART_IBRD, UART_FBRD,
+  UART_LCRH, UART_CR, UART_IFLS, UART_IMSC, UART_RIS, UART_MIS,
+  UART_ICR, UART_DMACR, UART_ITCR, UART_ITIP, UART_ITOP, UART_TDR,
+];
@@ -1,8 +1,30 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
 
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use self::constants::bits;
+use crate::peripheral::drivers::gpio::{self, constants::PinFunction};
+use crate::peripheral::drivers::interrupt::{self, constants::IrqSource};
+use crate::util::cpu;
+use crate::util::ring_buffer::RingBuffer;
+
 pub mod constants;
 
+/// Received data waiting to be read, filled from the UART interrupt.
+/// Entries hold the data byte and the error bits, as read from UART_DR.
+static RX_BUFFER: RingBuffer<u16, 512> = RingBuffer::new();
+/// Data waiting to be transmitted, drained from the UART interrupt.
+static TX_BUFFER: RingBuffer<u8, 512> = RingBuffer::new();
+/// Whether the buffers are being serviced by the UART interrupt.
+static INTERRUPT_DRIVEN: AtomicBool = AtomicBool::new(false);
+
+static OVERRUN_ERRORS: AtomicU32 = AtomicU32::new(0);
+static BREAK_ERRORS: AtomicU32 = AtomicU32::new(0);
+static PARITY_ERRORS: AtomicU32 = AtomicU32::new(0);
+static FRAMING_ERRORS: AtomicU32 = AtomicU32::new(0);
+static DROPPED_BYTES: AtomicU32 = AtomicU32::new(0);
+
 #[inline(always)]
 pub fn uart_transmit_fifo_empty() -> bool {
   constants::UART_FR.read_bit(7)
@@ -41,6 +63,11 @@ impl UartData {
     Self(data)
   }
 
+  #[inline(always)]
+  fn raw(&self) -> u32 {
+    self.0
+  }
+
   #[inline(always)]
   pub fn has_error(&self) -> bool {
     self.0 & (
@@ -95,21 +122,390 @@ pub fn uart_write_str(s: &str) {
   }
 }
 
-#[inline(always)]
 pub fn uart_write_byte(b: u8) {
+  if !INTERRUPT_DRIVEN.load(Ordering::Relaxed) || !cpu::irqs_enabled() {
+    // Nothing will drain the transmit buffer while IRQs are masked (or before interrupts are set up),
+    // so write directly, after whatever is still buffered to keep the output in order.
+    cpu::without_irqs(|| {
+      // SAFETY: IRQs are masked, so the interrupt handler (the only other consumer) can't run.
+      while let Some(buffered) = unsafe { TX_BUFFER.pop() } {
+        uart_write_polled(buffered);
+      }
+      uart_write_polled(b);
+    });
+    return;
+  }
+
+  loop {
+    let queued = cpu::without_irqs(|| {
+      // SAFETY: IRQs are masked, so this is the only producer right now.
+      let queued = unsafe { TX_BUFFER.push(b) };
+      uart_fill_transmit_fifo();
+      queued
+    });
+    if queued {
+      return;
+    }
+    // The buffer is full, wait for the interrupt handler to drain some of it.
+    uart_wait_for_interrupt(|| TX_BUFFER.is_full());
+  }
+}
+
+#[inline(always)]
+fn uart_write_polled(b: u8) {
   // Wait until we can send
   while uart_transmit_fifo_full() {}
   // Send the byte
   uart_write(b);
 }
 
-// TODO: Make this interrupt-driven
-#[inline(always)]
+/// Waits until everything in the transmit buffer has been sent.
+pub fn uart_flush() {
+  while !TX_BUFFER.is_empty() {
+    if INTERRUPT_DRIVEN.load(Ordering::Relaxed) && cpu::irqs_enabled() {
+      uart_wait_for_interrupt(|| !TX_BUFFER.is_empty());
+    } else {
+      cpu::without_irqs(|| {
+        // SAFETY: IRQs are masked, so the interrupt handler (the only other consumer) can't run.
+        while let Some(buffered) = unsafe { TX_BUFFER.pop() } {
+          uart_write_polled(buffered);
+        }
+      });
+    }
+  }
+  while uart_busy() {}
+}
+
+/// Reads a received byte if there is one, without blocking.
+pub fn uart_try_read() -> Option<UartData> {
+  if INTERRUPT_DRIVEN.load(Ordering::Relaxed) {
+    // SAFETY: IRQs are masked, and the interrupt handler is a producer, so this is the only consumer.
+    if let Some(raw) = cpu::without_irqs(|| unsafe { RX_BUFFER.pop() }) {
+      return Some(UartData::new(raw as u32));
+    }
+    // With IRQs masked (in an IRQ handler, a critical section or a panic) nothing moves bytes from the FIFO to the
+    // buffer, so they're read from the FIFO directly.
+    if cpu::irqs_enabled() {
+      return None;
+    }
+  }
+
+  if uart_receive_fifo_empty() {
+    return None;
+  }
+  let data = uart_read();
+  uart_record_errors(&data);
+  Some(data)
+}
+
+/// Reads a received byte, waiting until one arrives.
+/// When interrupt-driven, the core sleeps until the UART interrupt fires.
 pub fn uart_read_blocking() -> UartData {
-  // Wait until something is in the buffer
-  while uart_receive_fifo_empty() {}
-  // Read the byte
-  uart_read()
+  loop {
+    if let Some(data) = uart_try_read() {
+      return data;
+    }
+    if INTERRUPT_DRIVEN.load(Ordering::Relaxed) && cpu::irqs_enabled() {
+      uart_wait_for_interrupt(|| RX_BUFFER.is_empty());
+    }
+  }
+}
+
+// Sleeps until an interrupt arrives, unless `condition` became false.
+// The check happens with IRQs masked, so the wakeup can't be missed between the check and the sleep.
+fn uart_wait_for_interrupt(condition: impl Fn() -> bool) {
+  cpu::irq_disable();
+  if condition() {
+    // Returns once an interrupt is pending, even though it's masked.
+    cpu::wait_for_interrupt();
+  }
+  cpu::irq_enable();
+}
+
+/// Start servicing the receive and transmit buffers from the UART interrupt.
+/// Requires the interrupt controller to be initialized, see [interrupt::init].
+pub fn uart_enable_interrupts() {
+  interrupt::register_handler(IrqSource::Uart, uart_handle_interrupt);
+
+  cpu::without_irqs(|| {
+    // Receive interrupt when the receive FIFO is 1/2 full (the receive timeout covers anything less),
+    // transmit interrupt when the transmit FIFO drains to 1/8 full.
+    constants::UART_IFLS.write((bits::IFLS_1_2 << bits::IFLS_RX_SHIFT) | (bits::IFLS_1_8 << bits::IFLS_TX_SHIFT));
+    constants::UART_ICR.write(bits::INT_ALL);
+    constants::UART_IMSC.write(
+      (1 << bits::INT_RX) |
+      (1 << bits::INT_RT) |
+      (1 << bits::INT_FE) |
+      (1 << bits::INT_PE) |
+      (1 << bits::INT_BE) |
+      (1 << bits::INT_OE)
+    );
+    INTERRUPT_DRIVEN.store(true, Ordering::Relaxed);
+  });
+
+  interrupt::enable(IrqSource::Uart);
+}
+
+/// Stop using the UART interrupt, flushing the transmit buffer first.
+/// Data still in the receive buffer is discarded.
+pub fn uart_disable_interrupts() {
+  uart_flush();
+  interrupt::disable(IrqSource::Uart);
+  cpu::without_irqs(|| {
+    constants::UART_IMSC.write(0);
+    constants::UART_ICR.write(bits::INT_ALL);
+    INTERRUPT_DRIVEN.store(false, Ordering::Relaxed);
+    // SAFETY: IRQs are masked and the interrupt is disabled, so this is the only consumer.
+    unsafe { RX_BUFFER.clear() };
+  });
 }
 
-// TODO: implement other UART functions (baud rate setting, interrupts, etc.)
\ No newline at end of file
+fn uart_handle_interrupt() {
+  let status = constants::UART_MIS.read();
+
+  // Drain the receive FIFO
+  while !uart_receive_fifo_empty() {
+    let data = uart_read();
+    uart_record_errors(&data);
+    // SAFETY: This runs with IRQs masked, so it's the only producer.
+    // Only the data byte and the error bits are kept, see [UartData].
+    if !unsafe { RX_BUFFER.push((data.raw() & 0xFFF) as u16) } {
+      DROPPED_BYTES.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  // Refill the transmit FIFO
+  uart_fill_transmit_fifo();
+
+  constants::UART_ICR.write(status);
+}
+
+// Moves as much as possible from the transmit buffer into the transmit FIFO.
+// The transmit interrupt is only left enabled while there is buffered data left to send.
+// Must be called with IRQs masked.
+fn uart_fill_transmit_fifo() {
+  while !uart_transmit_fifo_full() {
+    // SAFETY: Caller ensured IRQs are masked, so this is the only consumer.
+    match unsafe { TX_BUFFER.pop() } {
+      Some(b) => uart_write(b),
+      None => break,
+    }
+  }
+  constants::UART_IMSC.write_bit(bits::INT_TX, if TX_BUFFER.is_empty() { 0 } else { 1 });
+}
+
+fn uart_record_errors(data: &UartData) {
+  if !data.has_error() {
+    return;
+  }
+  if data.overrun_error() {
+    OVERRUN_ERRORS.fetch_add(1, Ordering::Relaxed);
+  }
+  if data.break_error() {
+    BREAK_ERRORS.fetch_add(1, Ordering::Relaxed);
+  }
+  if data.parity_error() {
+    PARITY_ERRORS.fetch_add(1, Ordering::Relaxed);
+  }
+  if data.framing_error() {
+    FRAMING_ERRORS.fetch_add(1, Ordering::Relaxed);
+  }
+}
+
+/// Receive error counts since boot.
+#[derive(Clone, Copy, Debug)]
+pub struct UartErrorCounts {
+  /// Data arrived while the receive FIFO was full
+  pub overrun: u32,
+  /// Break conditions detected on the line
+  pub break_condition: u32,
+  /// Bytes received with a parity error
+  pub parity: u32,
+  /// Bytes received without a valid stop bit
+  pub framing: u32,
+  /// Bytes discarded because the receive buffer was full
+  pub dropped: u32,
+}
+
+pub fn uart_error_counts() -> UartErrorCounts {
+  UartErrorCounts {
+    overrun: OVERRUN_ERRORS.load(Ordering::Relaxed),
+    break_condition: BREAK_ERRORS.load(Ordering::Relaxed),
+    parity: PARITY_ERRORS.load(Ordering::Relaxed),
+    framing: FRAMING_ERRORS.load(Ordering::Relaxed),
+    dropped: DROPPED_BYTES.load(Ordering::Relaxed),
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataBits {
+  Five,
+  Six,
+  Seven,
+  Eight,
+}
+
+impl DataBits {
+  #[inline]
+  fn value(&self) -> u32 {
+    match *self {
+      DataBits::Five => 0b00,
+      DataBits::Six => 0b01,
+      DataBits::Seven => 0b10,
+      DataBits::Eight => 0b11,
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Parity {
+  None,
+  Even,
+  Odd,
+  /// Parity bit is always 1
+  Mark,
+  /// Parity bit is always 0
+  Space,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopBits {
+  One,
+  Two,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UartConfigError {
+  /// The baud rate can't be derived from [constants::UART_CLOCK_HZ]
+  UnsupportedBaudRate,
+}
+
+/// Line settings of the UART, applied with [uart_configure].
+/// Defaults to 115200 baud, 8 data bits, no parity, 1 stop bit and no flow control.
+#[derive(Clone, Copy, Debug)]
+pub struct UartConfig {
+  baud_rate: u32,
+  data_bits: DataBits,
+  parity: Parity,
+  stop_bits: StopBits,
+  flow_control: bool,
+}
+
+impl UartConfig {
+  pub const fn new() -> Self {
+    Self {
+      baud_rate: 115_200,
+      data_bits: DataBits::Eight,
+      parity: Parity::None,
+      stop_bits: StopBits::One,
+      flow_control: false,
+    }
+  }
+
+  #[must_use = "This function does not configure the UART until uart_configure() is called"]
+  pub const fn baud_rate(mut self, baud_rate: u32) -> Self {
+    self.baud_rate = baud_rate;
+    self
+  }
+
+  #[must_use = "This function does not configure the UART until uart_configure() is called"]
+  pub const fn data_bits(mut self, data_bits: DataBits) -> Self {
+    self.data_bits = data_bits;
+    self
+  }
+
+  #[must_use = "This function does not configure the UART until uart_configure() is called"]
+  pub const fn parity(mut self, parity: Parity) -> Self {
+    self.parity = parity;
+    self
+  }
+
+  #[must_use = "This function does not configure the UART until uart_configure() is called"]
+  pub const fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+    self.stop_bits = stop_bits;
+    self
+  }
+
+  /// Enables RTS/CTS hardware flow control, which also routes GPIO 16 and 17 to the UART.
+  #[must_use = "This function does not configure the UART until uart_configure() is called"]
+  pub const fn flow_control(mut self, enabled: bool) -> Self {
+    self.flow_control = enabled;
+    self
+  }
+
+  /// Integer and fractional baud rate divisors for a reference clock of `clock_hz`.
+  ///
+  /// The divisor is `clock_hz / (16 * baud_rate)`, with the fractional part in 1/64ths.
+  pub fn divisors(&self, clock_hz: u32) -> Result<(u32, u32), UartConfigError> {
+    if self.baud_rate == 0 {
+      return Err(UartConfigError::UnsupportedBaudRate);
+    }
+    // 64 * clock / (16 * baud), rounded to the nearest 1/64th.
+    let divisor = (4 * clock_hz as u64 + self.baud_rate as u64 / 2) / self.baud_rate as u64;
+    let integer = divisor >> 6;
+    let fractional = divisor & 0x3F;
+    if integer == 0 || integer > 0xFFFF {
+      return Err(UartConfigError::UnsupportedBaudRate);
+    }
+    Ok((integer as u32, fractional as u32))
+  }
+
+  fn line_control(&self) -> u32 {
+    let parity = match self.parity {
+      Parity::None => 0,
+      Parity::Even => (1 << bits::LCRH_PEN) | (1 << bits::LCRH_EPS),
+      Parity::Odd => 1 << bits::LCRH_PEN,
+      Parity::Mark => (1 << bits::LCRH_PEN) | (1 << bits::LCRH_SPS),
+      Parity::Space => (1 << bits::LCRH_PEN) | (1 << bits::LCRH_EPS) | (1 << bits::LCRH_SPS),
+    };
+    let stop_bits = match self.stop_bits {
+      StopBits::One => 0,
+      StopBits::Two => 1 << bits::LCRH_STP2,
+    };
+    (self.data_bits.value() << bits::LCRH_WLEN_SHIFT) | (1 << bits::LCRH_FEN) | parity | stop_bits
+  }
+}
+
+/// Reprograms the UART with new line settings.
+///
+/// Buffered output is sent with the old settings first. Follows the sequence from the PL011 manual:
+/// disable the UART, wait for the current character to finish, flush the FIFOs, reprogram, enable.
+pub fn uart_configure(config: &UartConfig) -> Result<(), UartConfigError> {
+  let (integer, fractional) = config.divisors(constants::UART_CLOCK_HZ)?;
+
+  uart_flush();
+
+  // TXD0 and RXD0
+  gpio::pin_function_set(14, PinFunction::ALT0);
+  gpio::pin_function_set(15, PinFunction::ALT0);
+  if config.flow_control {
+    // CTS0 and RTS0
+    gpio::pin_function_set(16, PinFunction::ALT3);
+    gpio::pin_function_set(17, PinFunction::ALT3);
+  } else {
+    // Back to their reset state, in case flow control was on before.
+    gpio::pin_function_set(16, PinFunction::INPUT);
+    gpio::pin_function_set(17, PinFunction::INPUT);
+  }
+
+  cpu::without_irqs(|| {
+    constants::UART_CR.write_bit(bits::CR_UARTEN, 0);
+    while uart_busy() {}
+    // Disabling the FIFOs flushes them
+    constants::UART_LCRH.write_bit(bits::LCRH_FEN, 0);
+
+    constants::UART_IBRD.write(integer);
+    constants::UART_FBRD.write(fractional);
+    // The divisors are only latched by a write to UART_LCRH, so this must come after them.
+    constants::UART_LCRH.write(config.line_control());
+
+    let flow_control = if config.flow_control {
+      (1 << bits::CR_RTSEN) | (1 << bits::CR_CTSEN)
+    } else {
+      0
+    };
+    constants::UART_CR.write((1 << bits::CR_UARTEN) | (1 << bits::CR_TXE) | (1 << bits::CR_RXE) | flow_control);
+  });
+
+  Ok(())
+}
\ No newline at end of file
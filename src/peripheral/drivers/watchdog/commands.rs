@@ -0,0 +1,40 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell commands for powering off and restarting the board.
+
+use crate::shell::{ArgKind, ArgSpec, Args, Command, CommandResult};
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "reboot",
+    summary: "restarts the board",
+    help: "The partition is passed on to the bootloader, 0 boots normally.",
+    args: &[ArgSpec::optional("partition", ArgKind::Integer, "boot partition, 0-62")],
+    run: reboot,
+  },
+  Command {
+    name: "shutdown",
+    summary: "shuts down the system",
+    help: "",
+    args: &[],
+    run: shutdown,
+  },
+];
+
+fn reboot(args: &Args) -> CommandResult {
+  let partition = args.integer("partition").unwrap_or(0);
+  // 63 is the special "halt" partition, which is what shutdown does.
+  if partition >= 63 {
+    return Err(crate::shell::CommandError::Failed("partition must be 0-62".into()));
+  }
+  crate::console::flush();
+  super::restart(partition as u8);
+  loop {
+    // Wait for the watchdog to fire.
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+  }
+}
+
+fn shutdown(_: &Args) -> CommandResult {
+  crate::console::flush();
+  super::power_off();
+}
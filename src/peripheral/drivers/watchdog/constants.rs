@@ -1,7 +1,7 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "Constants may be unused, they should be declared regardless of usage.")]
 
-use crate::util::mem::Register;
+use crate::util::mem::{NamedRegister, Register};
 
 // These registers are not documented in the BCM2835 ARM Peripherals manual, or anywhere else.
 // They are however used in the Linux kernel, and their addresses can be found there.
@@ -21,10 +21,27 @@ pub const PM_WDOG: Register = Register::from_addr(BASE + 0x24);
 pub const PM_PASSWORD: u32 = 0x5A000000;
 pub const PM_WDOG_TIME_SET: u32 = 0x000FFFFF;
 pub const PM_RSTC_WRCFG_CLR: u32 = 0xFFFFFFCF;
-pub const PM_RSTS_HADWRH_SET: u32 = 0x00000040;
 pub const PM_RSTC_WRCFG_SET: u32 = 0x00000030;
 pub const PM_RSTC_WRCFG_FULL_RESET: u32 = 0x00000020;
 pub const PM_RSTC_RESET: u32 = 0x00000102;
 
+// PM_RSTS bits recording the last reset: power on, or a hard, full or quick reset
+// by the watchdog, software or the debugger.
+pub const PM_RSTS_HADPOR_SET: u32 = 0x00001000;
+pub const PM_RSTS_HADSRH_SET: u32 = 0x00000400;
+pub const PM_RSTS_HADSRF_SET: u32 = 0x00000200;
+pub const PM_RSTS_HADSRQ_SET: u32 = 0x00000100;
+pub const PM_RSTS_HADWRH_SET: u32 = 0x00000040;
+pub const PM_RSTS_HADWRF_SET: u32 = 0x00000020;
+pub const PM_RSTS_HADWRQ_SET: u32 = 0x00000010;
+pub const PM_RSTS_HADDRH_SET: u32 = 0x00000004;
+pub const PM_RSTS_HADDRF_SET: u32 = 0x00000002;
+pub const PM_RSTS_HADDRQ_SET: u32 = 0x00000001;
+
 /// Mask to clear partition bits in PM_RSTS
 pub const PM_RSTS_PARTITION_CLR: u32 = 0xFFFFFAAA;
+
+/// Registers of this peripheral by name, for looking them up from the shell.
+pub const REGISTERS: &[NamedRegister] = crate::named_registers![
+  PM_RSTC, PM_RSTS, PM_WDOG,
+];
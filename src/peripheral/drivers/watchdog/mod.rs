@@ -1,5 +1,6 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 #![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+pub mod commands;
 pub mod constants;
 
 #[repr(transparent)]
@@ -83,6 +84,53 @@ pub fn restart(partition: u8) {
   );
 }
 
+/// What caused the last reset, see [reset_reason].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResetReason {
+  PowerOn,
+  Watchdog,
+  Software,
+  Debugger,
+  Unknown,
+}
+
+impl ResetReason {
+  pub fn name(&self) -> &'static str {
+    match self {
+      ResetReason::PowerOn => "power on",
+      ResetReason::Watchdog => "watchdog",
+      ResetReason::Software => "software",
+      ResetReason::Debugger => "debugger",
+      ResetReason::Unknown => "unknown",
+    }
+  }
+}
+
+/// What caused the last reset, according to PM_RSTS.
+/// Note that the partition bits [restart] writes share the register, so the firmware's reading of it is not exact either.
+pub fn reset_reason() -> ResetReason {
+  let status = constants::PM_RSTS.read();
+  let has = |bits: u32| status & bits != 0;
+  if has(constants::PM_RSTS_HADPOR_SET) {
+    ResetReason::PowerOn
+  } else if has(constants::PM_RSTS_HADWRH_SET | constants::PM_RSTS_HADWRF_SET | constants::PM_RSTS_HADWRQ_SET) {
+    ResetReason::Watchdog
+  } else if has(constants::PM_RSTS_HADSRH_SET | constants::PM_RSTS_HADSRF_SET | constants::PM_RSTS_HADSRQ_SET) {
+    ResetReason::Software
+  } else if has(constants::PM_RSTS_HADDRH_SET | constants::PM_RSTS_HADDRF_SET | constants::PM_RSTS_HADDRQ_SET) {
+    ResetReason::Debugger
+  } else {
+    ResetReason::Unknown
+  }
+}
+
+/// The partition the last [restart] asked the bootloader for, 0 being the default.
+pub fn reset_partition() -> u8 {
+  let status = constants::PM_RSTS.read();
+  // Partition bit n is stored in bit 2n, see restart.
+  (0..6).fold(0, |partition, bit| partition | (((status >> (bit * 2)) & 1) << bit) as u8)
+}
+
 /// NB! This will not return, the board will power off.
 /// This works by requesting partition 63 (halt) before firing the watchdog.
 /// The board will reboot into the bootloader, which will then not do anything,
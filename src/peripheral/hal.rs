@@ -0,0 +1,59 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Traits drivers can be written against instead of a specific peripheral.
+//!
+//! These mirror the `digital` traits of embedded-hal 1.0, with the same names and signatures,
+//! so drivers written against them only need their imports changed to use the real crate.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt::Debug;
+
+/// Gives a pin its error type.
+pub trait ErrorType {
+  type Error: Debug;
+}
+
+/// Level of a pin.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PinState {
+  Low,
+  High,
+}
+
+impl From<bool> for PinState {
+  fn from(high: bool) -> Self {
+    if high { PinState::High } else { PinState::Low }
+  }
+}
+
+/// A pin that can be driven high and low.
+pub trait OutputPin: ErrorType {
+  fn set_low(&mut self) -> Result<(), Self::Error>;
+
+  fn set_high(&mut self) -> Result<(), Self::Error>;
+
+  fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+    match state {
+      PinState::Low => self.set_low(),
+      PinState::High => self.set_high(),
+    }
+  }
+}
+
+/// An output pin that knows what it's driving.
+pub trait StatefulOutputPin: OutputPin {
+  fn is_set_high(&mut self) -> Result<bool, Self::Error>;
+
+  fn is_set_low(&mut self) -> Result<bool, Self::Error>;
+
+  fn toggle(&mut self) -> Result<(), Self::Error> {
+    let high = self.is_set_high()?;
+    self.set_state(PinState::from(!high))
+  }
+}
+
+/// A pin whose level can be read.
+pub trait InputPin: ErrorType {
+  fn is_high(&mut self) -> Result<bool, Self::Error>;
+
+  fn is_low(&mut self) -> Result<bool, Self::Error>;
+}
@@ -1,8 +1,16 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 pub mod drivers {
+  pub mod arm_timer;
   pub mod gpio;
+  pub mod interrupt;
+  pub mod mailbox;
+  pub mod mini_uart;
   pub mod timer;
   pub mod spi;
   pub mod uart;
   pub mod watchdog;
 }
+
+pub mod hal;
+pub mod registers;
+pub mod serial;
@@ -0,0 +1,51 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Every peripheral register the drivers know about, by name.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use super::drivers::{arm_timer, gpio, interrupt, mailbox, mini_uart, spi, timer, uart, watchdog};
+use crate::util::mem::NamedRegister;
+
+/// Size of the blocks peripherals are laid out in.
+pub const PAGE_SIZE: u32 = 0x1000;
+
+const TABLES: &[&[NamedRegister]] = &[
+  arm_timer::constants::REGISTERS,
+  gpio::constants::REGISTERS,
+  interrupt::constants::REGISTERS,
+  mailbox::constants::REGISTERS,
+  mini_uart::constants::REGISTERS,
+  spi::constants::REGISTERS,
+  timer::constants::REGISTERS,
+  uart::constants::REGISTERS,
+  watchdog::constants::REGISTERS,
+];
+
+pub fn all() -> impl Iterator<Item = &'static NamedRegister> {
+  TABLES.iter().flat_map(|table| table.iter())
+}
+
+/// The register called `name`, ignoring case.
+pub fn find(name: &str) -> Option<&'static NamedRegister> {
+  all().find(|register| register.name.eq_ignore_ascii_case(name))
+}
+
+/// The register at the physical address `address`.
+pub fn at(address: u32) -> Option<&'static NamedRegister> {
+  all().find(|register| register.register.address() == address)
+}
+
+/// Whether `address` is in the same 4 KiB block as a known register, i.e. belongs to a peripheral that has a driver.
+/// Other addresses in the peripheral range may not be backed by anything.
+pub fn is_known_peripheral(address: u32) -> bool {
+  all().any(|register| register.register.address() / PAGE_SIZE == address / PAGE_SIZE)
+}
+
+/// Turns a peripheral bus address, as used in the datasheet (`0x7Exxxxxx`), into the physical address the ARM uses.
+/// Other addresses are returned as they are.
+pub fn bus_to_physical(address: u32) -> u32 {
+  if address & 0xFF00_0000 == 0x7E00_0000 {
+    0x2000_0000 | (address & 0x00FF_FFFF)
+  } else {
+    address
+  }
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "This module may be unused, as it is providing peripheral functionality that may not be used anywhere")]
+
+use crate::peripheral::drivers::mailbox::{self, constants::Clock};
+use crate::peripheral::drivers::{mini_uart, uart};
+
+/// Baud rate the serial ports are brought up with.
+pub const DEFAULT_BAUD_RATE: u32 = 115_200;
+
+/// One of the two UARTs that can be used as a serial console.
+///
+/// Both are routed to GPIO 14/15, so only one of them can be in use at a time.
+/// On boards with wireless (e.g. the Pi Zero W) the PL011 is normally wired to Bluetooth,
+/// and the mini UART is the one on the GPIO header.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SerialPort {
+  /// PL011 UART (UART0), interrupt-driven
+  Uart0,
+  /// Mini UART (AUX UART1), polled
+  MiniUart,
+}
+
+impl SerialPort {
+  pub fn name(&self) -> &'static str {
+    match *self {
+      SerialPort::Uart0 => "uart0",
+      SerialPort::MiniUart => "mini",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "uart0" | "pl011" => Some(SerialPort::Uart0),
+      "mini" | "uart1" => Some(SerialPort::MiniUart),
+      _ => None,
+    }
+  }
+
+  /// Configures the port at [DEFAULT_BAUD_RATE] and routes GPIO 14/15 to it.
+  /// The PL011 additionally needs the interrupt controller to be initialized. The mini UART's baud rate depends on the
+  /// core clock, which is asked from the firmware, see [mini_uart::constants::SYSTEM_CLOCK_HZ].
+  pub fn init(&self) {
+    match *self {
+      SerialPort::Uart0 => {
+        // The default baud rate is always valid for the PL011.
+        let _ = uart::uart_configure(&uart::UartConfig::new().baud_rate(DEFAULT_BAUD_RATE));
+        uart::uart_enable_interrupts();
+      }
+      SerialPort::MiniUart => {
+        let clock_hz = match mailbox::clock_rate(Clock::Core) {
+          Ok(rate) if rate != 0 => rate,
+          _ => mini_uart::constants::SYSTEM_CLOCK_HZ,
+        };
+        // The default baud rate is valid for any core clock the firmware can set.
+        let _ = mini_uart::mini_uart_init(clock_hz, DEFAULT_BAUD_RATE);
+      }
+    }
+  }
+
+  #[inline]
+  pub fn write_byte(&self, b: u8) {
+    match *self {
+      SerialPort::Uart0 => uart::uart_write_byte(b),
+      SerialPort::MiniUart => mini_uart::mini_uart_write_byte(b),
+    }
+  }
+
+  #[inline]
+  pub fn write_str(&self, s: &str) {
+    for &b in s.as_bytes() {
+      self.write_byte(b);
+    }
+  }
+
+  /// Reads a received byte, waiting until one arrives.
+  #[inline]
+  pub fn read_blocking(&self) -> u8 {
+    match *self {
+      SerialPort::Uart0 => uart::uart_read_blocking().data(),
+      SerialPort::MiniUart => mini_uart::mini_uart_read_blocking(),
+    }
+  }
+
+  /// Reads a received byte if there is one, without blocking.
+  #[inline]
+  pub fn try_read(&self) -> Option<u8> {
+    match *self {
+      SerialPort::Uart0 => uart::uart_try_read().map(|data| data.data()),
+      SerialPort::MiniUart => mini_uart::mini_uart_try_read(),
+    }
+  }
+
+  /// Waits until all pending output has been sent.
+  pub fn flush(&self) {
+    match *self {
+      SerialPort::Uart0 => uart::uart_flush(),
+      SerialPort::MiniUart => mini_uart::mini_uart_flush(),
+    }
+  }
+}
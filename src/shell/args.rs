@@ -0,0 +1,372 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Splitting command lines into words, and parsing the words into typed arguments.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt;
+use core::iter::Peekable;
+use core::str::Chars;
+use core::time::Duration;
+
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use crate::peripheral::drivers::gpio::constants::PIN_COUNT;
+use crate::peripheral::registers;
+
+/// What an argument is parsed as.
+#[derive(Clone, Copy, Debug)]
+pub enum ArgKind {
+  /// Any text
+  Text,
+  /// Unsigned 32 bit integer, decimal or with a `0x`/`0b` prefix, `_` may be used as a separator
+  Integer,
+  /// A GPIO pin number
+  Pin,
+  /// A physical or peripheral bus address, or the name of a peripheral register like `UART_FR`
+  Address,
+  /// A duration with a unit (`us`, `ms`, `s` or `m`), plain numbers are milliseconds
+  Duration,
+  /// One of the listed words
+  Choice(&'static [&'static str]),
+  /// The name of a registered command
+  Command,
+}
+
+impl ArgKind {
+  /// Describes the values this kind takes, for help and error messages.
+  pub fn describe(&self) -> Describe {
+    Describe(*self)
+  }
+}
+
+/// Formats an [ArgKind] for humans, see [ArgKind::describe].
+pub struct Describe(ArgKind);
+
+impl fmt::Display for Describe {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.0 {
+      ArgKind::Text => write!(f, "text"),
+      ArgKind::Integer => write!(f, "integer"),
+      ArgKind::Pin => write!(f, "pin 0-{}", PIN_COUNT - 1),
+      ArgKind::Address => write!(f, "address or register name"),
+      ArgKind::Duration => write!(f, "duration"),
+      ArgKind::Choice(choices) => {
+        for (i, choice) in choices.iter().enumerate() {
+          write!(f, "{}{}", if i == 0 { "" } else { "|" }, choice)?;
+        }
+        Ok(())
+      }
+      ArgKind::Command => write!(f, "command"),
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arity {
+  Required,
+  Optional,
+  /// Any number of values, only allowed for the last argument
+  Many,
+}
+
+/// Describes one argument of a command.
+#[derive(Clone, Copy, Debug)]
+pub struct ArgSpec {
+  pub name: &'static str,
+  pub kind: ArgKind,
+  pub arity: Arity,
+  pub help: &'static str,
+}
+
+impl ArgSpec {
+  pub const fn required(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+    Self { name, kind, arity: Arity::Required, help }
+  }
+
+  pub const fn optional(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+    Self { name, kind, arity: Arity::Optional, help }
+  }
+
+  pub const fn many(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+    Self { name, kind, arity: Arity::Many, help }
+  }
+}
+
+/// A parsed argument.
+#[derive(Clone, Debug)]
+pub enum Value {
+  Text(String),
+  Integer(u32),
+  Pin(u8),
+  Duration(Duration),
+  /// One of the choices, or a command name
+  Word(&'static str),
+}
+
+#[derive(Clone, Debug)]
+pub enum ArgError {
+  /// A quote wasn't closed before the end of the line
+  UnterminatedQuote,
+  /// The line ended in a backslash
+  TrailingBackslash,
+  /// A `${` wasn't closed before the end of the line
+  UnterminatedBrace,
+  Missing(&'static str),
+  /// More values than the command takes, starting with this one
+  Unexpected(String),
+  Invalid { name: &'static str, kind: ArgKind, value: String },
+}
+
+impl fmt::Display for ArgError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ArgError::UnterminatedQuote => write!(f, "unterminated quote"),
+      ArgError::TrailingBackslash => write!(f, "nothing to escape after the backslash"),
+      ArgError::UnterminatedBrace => write!(f, "unterminated ${{"),
+      ArgError::Missing(name) => write!(f, "missing <{}>", name),
+      ArgError::Unexpected(value) => write!(f, "unexpected argument \"{}\"", value),
+      ArgError::Invalid { name, kind, value } => write!(f, "invalid <{}> \"{}\", expected {}", name, value, kind.describe()),
+    }
+  }
+}
+
+/// Splits a line into words at whitespace.
+///
+/// Single quotes keep everything between them as is. Inside double quotes and outside of quotes
+/// a backslash escapes the next character, with `\n` and `\t` being a newline and a tab, and
+/// `$NAME`, `${NAME}` and `$?` are replaced with what `lookup` returns for the name.
+/// Values are never split into words, and outside of quotes an empty value doesn't make a word on its own.
+pub fn tokenize(line: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<Vec<String>, ArgError> {
+  let mut words = Vec::new();
+  let mut word = String::new();
+  // Set once the current word has started, so that "" is a word too.
+  let mut in_word = false;
+  let mut chars = line.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    match c {
+      c if c.is_whitespace() => {
+        if in_word {
+          words.push(core::mem::take(&mut word));
+          in_word = false;
+        }
+      }
+      '\'' => {
+        in_word = true;
+        loop {
+          match chars.next() {
+            Some('\'') => break,
+            Some(c) => word.push(c),
+            None => return Err(ArgError::UnterminatedQuote),
+          }
+        }
+      }
+      '"' => {
+        in_word = true;
+        loop {
+          match chars.next() {
+            Some('"') => break,
+            Some('\\') => word.push(unescape(chars.next().ok_or(ArgError::UnterminatedQuote)?)),
+            Some('$') => word.push_str(&expand(&mut chars, &lookup)?),
+            Some(c) => word.push(c),
+            None => return Err(ArgError::UnterminatedQuote),
+          }
+        }
+      }
+      '\\' => {
+        in_word = true;
+        word.push(unescape(chars.next().ok_or(ArgError::TrailingBackslash)?));
+      }
+      '$' => {
+        let value = expand(&mut chars, &lookup)?;
+        in_word |= !value.is_empty();
+        word.push_str(&value);
+      }
+      c => {
+        in_word = true;
+        word.push(c);
+      }
+    }
+  }
+  if in_word {
+    words.push(word);
+  }
+  Ok(words)
+}
+
+/// Expands the variable after a `$`. A `$` that isn't followed by a name is kept as is.
+fn expand(chars: &mut Peekable<Chars>, lookup: &impl Fn(&str) -> Option<String>) -> Result<String, ArgError> {
+  let mut name = String::new();
+  match chars.peek() {
+    Some('?') => {
+      chars.next();
+      name.push('?');
+    }
+    Some('{') => {
+      chars.next();
+      loop {
+        match chars.next() {
+          Some('}') => break,
+          Some(c) => name.push(c),
+          None => return Err(ArgError::UnterminatedBrace),
+        }
+      }
+    }
+    _ => {
+      while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+        chars.next();
+        name.push(c);
+      }
+      if name.is_empty() {
+        return Ok("$".into());
+      }
+    }
+  }
+  Ok(lookup(&name).unwrap_or_default())
+}
+
+fn unescape(c: char) -> char {
+  match c {
+    'n' => '\n',
+    't' => '\t',
+    c => c,
+  }
+}
+
+/// Parses an unsigned integer, see [ArgKind::Integer].
+pub fn parse_integer(text: &str) -> Option<u32> {
+  let (digits, radix) = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+    (hex, 16)
+  } else if let Some(binary) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+    (binary, 2)
+  } else {
+    (text, 10)
+  };
+  if digits.is_empty() || digits.starts_with('_') {
+    return None;
+  }
+  digits.chars().filter(|&c| c != '_').try_fold(0u32, |value, c| {
+    value.checked_mul(radix)?.checked_add(c.to_digit(radix)?)
+  })
+}
+
+/// Parses a duration, see [ArgKind::Duration].
+pub fn parse_duration(text: &str) -> Option<Duration> {
+  let split = text.find(|c: char| !c.is_ascii_digit() && c != '_').unwrap_or(text.len());
+  let value = parse_integer(&text[..split])? as u64;
+  match &text[split..] {
+    "us" => Some(Duration::from_micros(value)),
+    "" | "ms" => Some(Duration::from_millis(value)),
+    "s" => Some(Duration::from_secs(value)),
+    "m" => Some(Duration::from_secs(value * 60)),
+    _ => None,
+  }
+}
+
+/// Parses a GPIO pin number, see [ArgKind::Pin].
+pub fn parse_pin(text: &str) -> Option<u8> {
+  parse_integer(text).filter(|&pin| pin < PIN_COUNT).map(|pin| pin as u8)
+}
+
+/// Parses an address, see [ArgKind::Address].
+pub fn parse_address(text: &str) -> Option<u32> {
+  match registers::find(text) {
+    Some(register) => Some(register.register.address()),
+    None => parse_integer(text).map(registers::bus_to_physical),
+  }
+}
+
+/// Arguments of a command, parsed according to its [ArgSpec]s.
+pub struct Args {
+  values: Vec<(&'static str, Value)>,
+}
+
+impl Args {
+  /// Parses `words` (without the command name) according to `specs`.
+  /// `is_command` tells whether a word names a registered command, for [ArgKind::Command].
+  pub fn parse(specs: &'static [ArgSpec], words: &[String], is_command: impl Fn(&str) -> Option<&'static str>) -> Result<Self, ArgError> {
+    let mut values = Vec::new();
+    let mut words = words.iter().peekable();
+
+    for spec in specs {
+      loop {
+        let Some(word) = words.peek() else {
+          if spec.arity == Arity::Required {
+            return Err(ArgError::Missing(spec.name));
+          }
+          break;
+        };
+        let value = match spec.kind {
+          ArgKind::Text => Some(Value::Text(word.as_str().into())),
+          ArgKind::Integer => parse_integer(word).map(Value::Integer),
+          ArgKind::Pin => parse_pin(word).map(Value::Pin),
+          ArgKind::Address => parse_address(word).map(Value::Integer),
+          ArgKind::Duration => parse_duration(word).map(Value::Duration),
+          ArgKind::Choice(choices) => choices.iter().find(|&&choice| choice == word.as_str()).map(|&choice| Value::Word(choice)),
+          ArgKind::Command => is_command(word).map(Value::Word),
+        };
+        let Some(value) = value else {
+          return Err(ArgError::Invalid { name: spec.name, kind: spec.kind, value: (*word).clone() });
+        };
+        values.push((spec.name, value));
+        words.next();
+        if spec.arity != Arity::Many {
+          break;
+        }
+      }
+    }
+
+    match words.next() {
+      Some(word) => Err(ArgError::Unexpected(word.clone())),
+      None => Ok(Self { values }),
+    }
+  }
+
+  /// The first value of the argument `name`, if it was given.
+  pub fn get(&self, name: &str) -> Option<&Value> {
+    self.values.iter().find(|(value_name, _)| *value_name == name).map(|(_, value)| value)
+  }
+
+  /// Every value of the argument `name`, for [Arity::Many] arguments.
+  pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+    self.values.iter().filter(move |(value_name, _)| *value_name == name).map(|(_, value)| value)
+  }
+
+  pub fn text(&self, name: &str) -> Option<&str> {
+    match self.get(name)? {
+      Value::Text(text) => Some(text),
+      Value::Word(word) => Some(word),
+      _ => None,
+    }
+  }
+
+  /// The value of an [ArgKind::Integer] or [ArgKind::Address] argument.
+  pub fn integer(&self, name: &str) -> Option<u32> {
+    match self.get(name)? {
+      Value::Integer(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub fn pin(&self, name: &str) -> Option<u8> {
+    match self.get(name)? {
+      Value::Pin(pin) => Some(*pin),
+      _ => None,
+    }
+  }
+
+  pub fn duration(&self, name: &str) -> Option<Duration> {
+    match self.get(name)? {
+      Value::Duration(duration) => Some(*duration),
+      _ => None,
+    }
+  }
+
+  /// The value of a [ArgKind::Choice] or [ArgKind::Command] argument.
+  pub fn word(&self, name: &str) -> Option<&'static str> {
+    match self.get(name)? {
+      Value::Word(word) => Some(word),
+      _ => None,
+    }
+  }
+}
@@ -0,0 +1,269 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Commands the shell always has.
+
+use core::hint::spin_loop;
+
+use rust_alloc::vec::Vec;
+
+use super::args::{parse_integer, ArgKind, ArgSpec, Args, Value};
+use super::registry::{self, Command, CommandError, CommandResult};
+use super::{env, script};
+use crate::console;
+use crate::log::{self, LogLevel};
+use crate::peripheral::serial::SerialPort;
+use crate::time::Deadline;
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "console",
+    summary: "shows or switches the serial port the console is on",
+    help: "",
+    args: &[ArgSpec::optional("port", ArgKind::Choice(&["uart0", "mini"]), "serial port to switch to")],
+    run: console,
+  },
+  Command {
+    name: "echo",
+    summary: "prints the text back to the terminal",
+    help: "",
+    args: &[ArgSpec::many("text", ArgKind::Text, "words to print, separated by spaces")],
+    run: echo,
+  },
+  Command {
+    name: "env",
+    summary: "lists the shell variables",
+    help: "",
+    args: &[],
+    run: env,
+  },
+  Command {
+    name: "false",
+    summary: "fails with exit status 1",
+    help: "",
+    args: &[],
+    run: |_| Err(CommandError::Exit(1)),
+  },
+  Command {
+    name: "help",
+    summary: "lists the commands, or describes one of them",
+    help: "",
+    args: &[ArgSpec::optional("command", ArgKind::Command, "command to describe")],
+    run: help,
+  },
+  Command {
+    name: "loglevel",
+    summary: "shows or sets the log level",
+    help: "Messages less severe than the log level are not printed.",
+    args: &[ArgSpec::optional("level", ArgKind::Choice(&["error", "warn", "info", "debug", "trace"]), "level to set")],
+    run: loglevel,
+  },
+  Command {
+    name: "run",
+    summary: "runs a script built into the kernel, or lists them",
+    help: "\
+Scripts are the same as what's typed into the shell, one command per line:
+  a; b          runs a, then b
+  a && b        runs b if a succeeded
+  a || b        runs b if a failed
+  $NAME ${NAME} value of a variable, $? is the exit status of the last command
+  # ...         comment until the end of the line
+  if <commands>   runs the lines up to 'else' or 'end' if the commands succeeded,
+  else            and the lines after 'else' up to 'end' if they failed
+  end
+  repeat <count>  runs the lines up to 'end' count times
+  end
+Ctrl-C stops the script.",
+    args: &[ArgSpec::optional("script", ArgKind::Text, "name of the script")],
+    run,
+  },
+  Command {
+    name: "set",
+    summary: "sets a shell variable",
+    help: "Variables are expanded in command lines as $NAME or ${NAME}. PS1 is the prompt.",
+    args: &[
+      ArgSpec::required("name", ArgKind::Text, "letters, digits and underscores, not starting with a digit"),
+      ArgSpec::many("value", ArgKind::Text, "words of the value, separated by spaces"),
+    ],
+    run: set,
+  },
+  Command {
+    name: "sleep",
+    summary: "waits for a while",
+    help: "",
+    args: &[ArgSpec::required("time", ArgKind::Duration, "how long to wait")],
+    run: sleep,
+  },
+  Command {
+    name: "test",
+    summary: "checks a condition, for 'if' and '&&'",
+    help: "\
+Succeeds if the expression is true:
+  <text>                 text isn't empty
+  -n <text>, -z <text>   text isn't empty, is empty
+  <a> = <b>, <a> != <b>  texts are equal, not equal
+  <a> -eq <b>            integers are equal, also -ne, -lt, -le, -gt and -ge
+  ! <expression>         expression is false",
+    args: &[ArgSpec::many("expression", ArgKind::Text, "expression to check")],
+    run: test,
+  },
+  Command {
+    name: "true",
+    summary: "succeeds without doing anything",
+    help: "",
+    args: &[],
+    run: |_| Ok(()),
+  },
+  Command {
+    name: "unset",
+    summary: "removes shell variables",
+    help: "",
+    args: &[ArgSpec::many("name", ArgKind::Text, "variables to remove")],
+    run: unset,
+  },
+];
+
+fn console(args: &Args) -> CommandResult {
+  match args.word("port").and_then(SerialPort::from_name) {
+    None => println!("Current console: {}", console::port().name()),
+    Some(port) => {
+      println!("Switching console to {}", port.name());
+      console::set_port(port);
+    }
+  }
+  Ok(())
+}
+
+fn echo(args: &Args) -> CommandResult {
+  for (i, value) in args.all("text").enumerate() {
+    if let Value::Text(text) = value {
+      print!("{}{}", if i == 0 { "" } else { " " }, text);
+    }
+  }
+  println!();
+  Ok(())
+}
+
+fn help(args: &Args) -> CommandResult {
+  let Some(command) = args.word("command").and_then(registry::find) else {
+    let commands = registry::commands();
+    let width = commands.iter().map(|command| command.name.len()).max().unwrap_or(0);
+    println!("Supported commands, 'help <command>' describes one:");
+    for command in commands {
+      println!("  {:width$}  {}", command.name, command.summary, width = width);
+    }
+    return Ok(());
+  };
+
+  println!("Usage: {}", command.usage());
+  let (first, rest) = command.summary.split_at(command.summary.len().min(1));
+  println!("{}{}", first.to_ascii_uppercase(), rest);
+  if !command.help.is_empty() {
+    println!("{}", command.help);
+  }
+  if !command.args.is_empty() {
+    let width = command.args.iter().map(|arg| arg.name.len()).max().unwrap_or(0);
+    println!("Arguments:");
+    for arg in command.args {
+      println!("  {:width$}  {} ({})", arg.name, arg.help, arg.kind.describe(), width = width);
+    }
+  }
+  Ok(())
+}
+
+fn loglevel(args: &Args) -> CommandResult {
+  match args.word("level").and_then(LogLevel::from_name) {
+    None => println!("Current log level: {}", log::level().name()),
+    Some(level) => log::set_level(level),
+  }
+  Ok(())
+}
+
+fn env(_: &Args) -> CommandResult {
+  for (name, value) in env::variables() {
+    println!("{}={}", name, value);
+  }
+  Ok(())
+}
+
+fn run(args: &Args) -> CommandResult {
+  let Some(name) = args.text("script") else {
+    println!("Scripts:");
+    for script in script::SCRIPTS {
+      println!("  {}", script.name);
+    }
+    return Ok(());
+  };
+  let Some(script) = script::find(name) else {
+    return Err(CommandError::Failed(rust_alloc::format!("No script called \"{}\"", name)));
+  };
+  match script::run(script.source) {
+    Some(0) => Ok(()),
+    Some(status) => Err(CommandError::Exit(status)),
+    None => Err(CommandError::Failed("run: scripts are nested too deeply".into())),
+  }
+}
+
+fn set(args: &Args) -> CommandResult {
+  let name = args.text("name").unwrap_or_default();
+  if !env::is_valid_name(name) {
+    return Err(CommandError::Failed(rust_alloc::format!("set: invalid variable name \"{}\"", name)));
+  }
+  let value = texts(args, "value").collect::<Vec<_>>().join(" ");
+  env::set(name, &value);
+  Ok(())
+}
+
+fn sleep(args: &Args) -> CommandResult {
+  let deadline = Deadline::after(args.duration("time").unwrap_or_default());
+  while !deadline.has_passed() {
+    if script::interrupted() {
+      return Err(CommandError::Exit(script::INTERRUPTED_STATUS));
+    }
+    spin_loop();
+  }
+  Ok(())
+}
+
+fn test(args: &Args) -> CommandResult {
+  let words: Vec<&str> = texts(args, "expression").collect();
+  let (negate, words) = match words.split_first() {
+    Some((&"!", rest)) => (true, rest),
+    _ => (false, &words[..]),
+  };
+  let result = match words {
+    [] => false,
+    [text] | ["-n", text] => !text.is_empty(),
+    ["-z", text] => text.is_empty(),
+    [a, "=", b] => a == b,
+    [a, "!=", b] => a != b,
+    [a, operator @ ("-eq" | "-ne" | "-lt" | "-le" | "-gt" | "-ge"), b] => {
+      let (Some(a), Some(b)) = (parse_integer(a), parse_integer(b)) else {
+        return Err(CommandError::Failed(rust_alloc::format!("test: {} compares integers", operator)));
+      };
+      match *operator {
+        "-eq" => a == b,
+        "-ne" => a != b,
+        "-lt" => a < b,
+        "-le" => a <= b,
+        "-gt" => a > b,
+        _ => a >= b,
+      }
+    }
+    _ => return Err(CommandError::Failed("test: unknown expression, see 'help test'".into())),
+  };
+  if result != negate { Ok(()) } else { Err(CommandError::Exit(1)) }
+}
+
+fn unset(args: &Args) -> CommandResult {
+  for name in texts(args, "name") {
+    env::unset(name);
+  }
+  Ok(())
+}
+
+/// Every value of a [ArgKind::Text] argument that takes many values.
+fn texts<'a>(args: &'a Args, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+  args.all(name).filter_map(|value| match value {
+    Value::Text(text) => Some(text.as_str()),
+    _ => None,
+  })
+}
@@ -0,0 +1,49 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell variables, expanded as `$NAME` or `${NAME}`, and the exit status of the last command as `$?`.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use rust_alloc::collections::BTreeMap;
+use rust_alloc::string::{String, ToString};
+use rust_alloc::vec::Vec;
+
+use crate::sync::SpinLock;
+
+static VARIABLES: SpinLock<BTreeMap<String, String>> = SpinLock::new(BTreeMap::new());
+static LAST_STATUS: AtomicU8 = AtomicU8::new(0);
+
+/// Whether `name` can be used as a variable name: a letter or underscore, followed by letters, digits and underscores.
+pub fn is_valid_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub fn get(name: &str) -> Option<String> {
+  if name == "?" {
+    return Some(last_status().to_string());
+  }
+  VARIABLES.with(|variables| variables.get(name).cloned())
+}
+
+pub fn set(name: &str, value: &str) {
+  VARIABLES.with(|variables| variables.insert(name.into(), value.into()));
+}
+
+pub fn unset(name: &str) {
+  VARIABLES.with(|variables| variables.remove(name));
+}
+
+/// Every variable and its value, sorted by name.
+pub fn variables() -> Vec<(String, String)> {
+  VARIABLES.with(|variables| variables.iter().map(|(name, value)| (name.clone(), value.clone())).collect())
+}
+
+/// Exit status of the last command, 0 meaning success.
+pub fn last_status() -> u8 {
+  LAST_STATUS.load(Ordering::Relaxed)
+}
+
+pub fn set_last_status(status: u8) {
+  LAST_STATUS.store(status, Ordering::Relaxed);
+}
@@ -0,0 +1,366 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Line editor for the shell, reading from the console and understanding what VT100 style terminals send.
+//!
+//! - Left/right, Home/End, Ctrl-A/E and Ctrl-B/F move the cursor
+//! - Backspace, Delete and Ctrl-D delete a character, Ctrl-K/U delete to the end/start of the line, Ctrl-W the word before the cursor
+//! - Up/down and Ctrl-P/N go through the history
+//! - Tab completes the word before the cursor, Ctrl-L clears the screen and Ctrl-C discards the line
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use rust_alloc::collections::VecDeque;
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use crate::console;
+
+/// Lines kept in the history.
+const HISTORY_SIZE: usize = 32;
+/// Longest line the editor takes, anything typed beyond that is ignored.
+pub const MAX_LINE_LENGTH: usize = 1024;
+
+const ESC: u8 = 0x1B;
+const DEL: u8 = 0x7F;
+
+/// The control character sent for Ctrl and `key`.
+const fn ctrl(key: u8) -> u8 {
+  key & 0x1F
+}
+
+/// Finds completions for the word that ends at the end of `line`, adding them to `candidates`.
+/// The candidates are whole words, not just the part that's missing.
+pub type Completer = fn(line: &str, candidates: &mut Vec<&'static str>);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+  Normal,
+  /// Got ESC
+  Escape,
+  /// Got ESC [, with the numeric parameter so far
+  Csi(u16),
+  /// Got ESC O, which some terminals send Home and End as
+  Ss3,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Key {
+  Char(u8),
+  Enter,
+  Backspace,
+  Delete,
+  Left,
+  Right,
+  Up,
+  Down,
+  Home,
+  End,
+  Tab,
+  KillToEnd,
+  KillToStart,
+  KillWord,
+  ClearScreen,
+  Cancel,
+}
+
+pub struct LineEditor {
+  /// Only ever holds printable ASCII, so byte and character positions are the same
+  line: String,
+  cursor: usize,
+  history: VecDeque<String>,
+  /// Position in the history while going through it
+  history_index: Option<usize>,
+  /// The line that was being edited before going through the history
+  saved_line: String,
+  escape: EscapeState,
+  /// Set if the last key was a tab that couldn't complete anything by itself
+  listed_completions: bool,
+}
+
+impl LineEditor {
+  pub const fn new() -> Self {
+    Self {
+      line: String::new(),
+      cursor: 0,
+      history: VecDeque::new(),
+      history_index: None,
+      saved_line: String::new(),
+      escape: EscapeState::Normal,
+      listed_completions: false,
+    }
+  }
+
+  /// Shows `prompt` and reads a line from the console, adding it to the history. Returns `None` if Ctrl-C discarded the line.
+  pub fn read_line(&mut self, prompt: &str, completer: Completer) -> Option<String> {
+    self.line.clear();
+    self.cursor = 0;
+    self.history_index = None;
+    self.escape = EscapeState::Normal;
+    self.listed_completions = false;
+    print!("{}", prompt);
+
+    loop {
+      let Some(key) = self.decode(console::read_byte()) else {
+        continue;
+      };
+      if key != Key::Tab {
+        self.listed_completions = false;
+      }
+      match key {
+        Key::Enter => break,
+        Key::Char(c) => self.insert(c),
+        Key::Backspace => {
+          if self.cursor > 0 {
+            self.cursor -= 1;
+            self.line.remove(self.cursor);
+            self.refresh(prompt);
+          }
+        }
+        Key::Delete => {
+          if self.cursor < self.line.len() {
+            self.line.remove(self.cursor);
+            self.refresh(prompt);
+          }
+        }
+        Key::Left => {
+          if self.cursor > 0 {
+            self.cursor -= 1;
+            print!("\x1b[D");
+          }
+        }
+        Key::Right => {
+          if self.cursor < self.line.len() {
+            self.cursor += 1;
+            print!("\x1b[C");
+          }
+        }
+        Key::Home => {
+          self.cursor = 0;
+          self.refresh(prompt);
+        }
+        Key::End => {
+          self.cursor = self.line.len();
+          self.refresh(prompt);
+        }
+        Key::Up => self.history_previous(prompt),
+        Key::Down => self.history_next(prompt),
+        Key::Tab => self.complete(prompt, completer),
+        Key::KillToEnd => {
+          self.line.truncate(self.cursor);
+          self.refresh(prompt);
+        }
+        Key::KillToStart => {
+          self.line.replace_range(..self.cursor, "");
+          self.cursor = 0;
+          self.refresh(prompt);
+        }
+        Key::KillWord => {
+          let start = self.word_start();
+          self.line.replace_range(start..self.cursor, "");
+          self.cursor = start;
+          self.refresh(prompt);
+        }
+        Key::ClearScreen => {
+          print!("\x1b[2J\x1b[H");
+          self.refresh(prompt);
+        }
+        Key::Cancel => {
+          println!("^C");
+          self.line.clear();
+          return None;
+        }
+      }
+    }
+
+    println!();
+    let line = core::mem::take(&mut self.line);
+    self.add_history(&line);
+    Some(line)
+  }
+
+  /// Turns input bytes into keys, keeping track of escape sequences.
+  fn decode(&mut self, byte: u8) -> Option<Key> {
+    match self.escape {
+      EscapeState::Normal => match byte {
+        ESC => {
+          self.escape = EscapeState::Escape;
+          None
+        }
+        b'\r' | b'\n' => Some(Key::Enter),
+        b'\t' => Some(Key::Tab),
+        0x08 | DEL => Some(Key::Backspace),
+        b if b == ctrl(b'A') => Some(Key::Home),
+        b if b == ctrl(b'B') => Some(Key::Left),
+        b if b == ctrl(b'C') => Some(Key::Cancel),
+        b if b == ctrl(b'D') => Some(Key::Delete),
+        b if b == ctrl(b'E') => Some(Key::End),
+        b if b == ctrl(b'F') => Some(Key::Right),
+        b if b == ctrl(b'K') => Some(Key::KillToEnd),
+        b if b == ctrl(b'L') => Some(Key::ClearScreen),
+        b if b == ctrl(b'N') => Some(Key::Down),
+        b if b == ctrl(b'P') => Some(Key::Up),
+        b if b == ctrl(b'U') => Some(Key::KillToStart),
+        b if b == ctrl(b'W') => Some(Key::KillWord),
+        b if b.is_ascii_graphic() || b == b' ' => Some(Key::Char(b)),
+        _ => None,
+      },
+      EscapeState::Escape => {
+        self.escape = match byte {
+          b'[' => EscapeState::Csi(0),
+          b'O' => EscapeState::Ss3,
+          _ => EscapeState::Normal,
+        };
+        None
+      }
+      EscapeState::Csi(param) => {
+        if byte.is_ascii_digit() {
+          self.escape = EscapeState::Csi(param.saturating_mul(10).saturating_add((byte - b'0') as u16));
+          return None;
+        }
+        self.escape = EscapeState::Normal;
+        match (byte, param) {
+          (b'A', _) => Some(Key::Up),
+          (b'B', _) => Some(Key::Down),
+          (b'C', _) => Some(Key::Right),
+          (b'D', _) => Some(Key::Left),
+          (b'H', _) => Some(Key::Home),
+          (b'F', _) => Some(Key::End),
+          (b'~', 1 | 7) => Some(Key::Home),
+          (b'~', 3) => Some(Key::Delete),
+          (b'~', 4 | 8) => Some(Key::End),
+          // Anything else ends at the first byte in this range, keep skipping until then.
+          (0x40..=0x7E, _) => None,
+          _ => {
+            self.escape = EscapeState::Csi(param);
+            None
+          }
+        }
+      }
+      EscapeState::Ss3 => {
+        self.escape = EscapeState::Normal;
+        match byte {
+          b'H' => Some(Key::Home),
+          b'F' => Some(Key::End),
+          _ => None,
+        }
+      }
+    }
+  }
+
+  fn insert(&mut self, c: u8) {
+    if self.line.len() >= MAX_LINE_LENGTH {
+      return;
+    }
+    self.line.insert(self.cursor, c as char);
+    self.cursor += 1;
+    if self.cursor == self.line.len() {
+      // Typing at the end of the line, just echo it.
+      console::write_bytes(&[c]);
+    } else {
+      // Rewrite the rest of the line and move back to the cursor.
+      print!("{}\x1b[{}D", &self.line[self.cursor - 1..], self.line.len() - self.cursor);
+    }
+  }
+
+  // Redraws the whole line and puts the terminal's cursor where ours is.
+  fn refresh(&self, prompt: &str) {
+    print!("\r{}{}\x1b[K", prompt, self.line);
+    let back = self.line.len() - self.cursor;
+    if back > 0 {
+      print!("\x1b[{}D", back);
+    }
+  }
+
+  fn replace_line(&mut self, prompt: &str, line: String) {
+    self.line = line;
+    self.cursor = self.line.len();
+    self.refresh(prompt);
+  }
+
+  // Start of the word before the cursor, skipping the spaces right before it.
+  fn word_start(&self) -> usize {
+    let before = self.line[..self.cursor].trim_end_matches(' ');
+    before.rfind(' ').map_or(0, |space| space + 1)
+  }
+
+  fn add_history(&mut self, line: &str) {
+    if line.trim().is_empty() || self.history.back().is_some_and(|last| last == line) {
+      return;
+    }
+    if self.history.len() == HISTORY_SIZE {
+      self.history.pop_front();
+    }
+    self.history.push_back(String::from(line));
+  }
+
+  fn history_previous(&mut self, prompt: &str) {
+    let index = match self.history_index {
+      None if self.history.is_empty() => return,
+      None => {
+        self.saved_line = core::mem::take(&mut self.line);
+        self.history.len() - 1
+      }
+      Some(0) => return,
+      Some(index) => index - 1,
+    };
+    self.history_index = Some(index);
+    self.replace_line(prompt, self.history[index].clone());
+  }
+
+  fn history_next(&mut self, prompt: &str) {
+    let Some(index) = self.history_index else {
+      return;
+    };
+    if index + 1 < self.history.len() {
+      self.history_index = Some(index + 1);
+      self.replace_line(prompt, self.history[index + 1].clone());
+    } else {
+      self.history_index = None;
+      let line = core::mem::take(&mut self.saved_line);
+      self.replace_line(prompt, line);
+    }
+  }
+
+  fn complete(&mut self, prompt: &str, completer: Completer) {
+    let start = self.line[..self.cursor].rfind(' ').map_or(0, |space| space + 1);
+    let word = &self.line[start..self.cursor];
+    let mut candidates = Vec::new();
+    completer(&self.line[..self.cursor], &mut candidates);
+    candidates.retain(|candidate| candidate.starts_with(word));
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let completion = match candidates.as_slice() {
+      [] => return,
+      [only] => {
+        let mut completion = String::from(&only[word.len()..]);
+        completion.push(' ');
+        completion
+      }
+      [first, rest @ ..] => {
+        let common = rest.iter().fold(first.len(), |common, candidate| {
+          first.bytes().zip(candidate.bytes()).take(common).take_while(|(a, b)| a == b).count()
+        });
+        String::from(&first[word.len()..common])
+      }
+    };
+
+    if completion.is_empty() {
+      // Nothing to add, list the candidates on a second tab.
+      if self.listed_completions {
+        println!();
+        for candidate in &candidates {
+          print!("{}  ", candidate);
+        }
+        println!();
+        self.refresh(prompt);
+      }
+      self.listed_completions = true;
+      return;
+    }
+    if self.line.len() + completion.len() > MAX_LINE_LENGTH {
+      return;
+    }
+    self.line.insert_str(self.cursor, &completion);
+    self.cursor += completion.len();
+    self.refresh(prompt);
+  }
+}
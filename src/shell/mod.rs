@@ -0,0 +1,109 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Interactive shell on the console.
+//!
+//! Commands are registered by the subsystems implementing them, see [register]. Lines typed into the shell are
+//! scripts, see [script] for what they can do.
+
+mod args;
+mod builtins;
+mod env;
+mod line_editor;
+mod registry;
+mod script;
+
+use rust_alloc::vec::Vec;
+
+use self::line_editor::LineEditor;
+use crate::peripheral::registers;
+
+pub use self::args::{ArgKind, ArgSpec, Args};
+pub use self::registry::{register, Command, CommandError, CommandResult};
+pub use self::script::{interrupted, INTERRUPTED_STATUS};
+
+/// Prompt shown when `PS1` isn't set.
+const PROMPT: &str = "$ ";
+/// Prompt shown while reading the rest of an `if` or `repeat` block.
+const CONTINUATION_PROMPT: &str = "> ";
+
+pub fn shell_main() -> () {
+  register(builtins::COMMANDS);
+  println!("Entering shell mode.");
+  if let Some(boot) = script::find("boot") {
+    script::run(boot.source);
+  }
+
+  let mut editor = LineEditor::new();
+  loop {
+    let prompt = env::get("PS1").unwrap_or_else(|| PROMPT.into());
+    let Some(mut source) = editor.read_line(&prompt, complete) else {
+      continue;
+    };
+    while script::is_incomplete(&source) {
+      let Some(line) = editor.read_line(CONTINUATION_PROMPT, complete) else {
+        source.clear();
+        break;
+      };
+      source.push('\n');
+      source.push_str(&line);
+    }
+    script::run(&source);
+  }
+}
+
+/// Runs a single command, reporting errors on the console and setting `$?` to its exit status.
+pub fn run_line(line: &str) -> CommandResult {
+  let (command, result) = run_words(line);
+  if let Err(error) = &result {
+    match (error, command) {
+      (CommandError::Args(error), Some(command)) => println!("{}: {}\nUsage: {}", command.name, error, command.usage()),
+      (CommandError::Args(error), None) => println!("{}", error),
+      (CommandError::Failed(message), _) => println!("{}", message),
+      (CommandError::UnknownCommand(name), _) => {
+        println!("Unknown command \"{}\". Type 'help' for a list of commands.", name)
+      }
+      (CommandError::Exit(_), _) => {}
+    }
+  }
+  env::set_last_status(result.as_ref().map_or_else(CommandError::status, |_| 0));
+  result
+}
+
+// Also returns the command that ran, if the line got that far, for its usage line.
+fn run_words(line: &str) -> (Option<&'static Command>, CommandResult) {
+  let words = match args::tokenize(line, env::get) {
+    Ok(words) => words,
+    Err(error) => return (None, Err(error.into())),
+  };
+  let Some((name, words)) = words.split_first() else {
+    return (None, Ok(()));
+  };
+  let Some(command) = registry::find(name) else {
+    return (None, Err(CommandError::UnknownCommand(name.clone())));
+  };
+  let result = Args::parse(command.args, words, |word| registry::find(word).map(|command| command.name))
+    .map_err(CommandError::Args)
+    .and_then(|args| (command.run)(&args));
+  (Some(command), result)
+}
+
+fn complete(line: &str, candidates: &mut Vec<&'static str>) {
+  let words: Vec<&str> = line.split_whitespace().collect();
+  // Index of the word being completed, a trailing space starts a new one.
+  let index = if line.is_empty() || line.ends_with(' ') { words.len() } else { words.len() - 1 };
+  if index == 0 {
+    candidates.extend(registry::commands().iter().map(|command| command.name));
+    return;
+  }
+  let Some(command) = registry::find(words[0]) else {
+    return;
+  };
+  // The last argument takes the rest of the words if it takes many values.
+  let spec = command.args.get(index - 1)
+    .or_else(|| command.args.last().filter(|spec| spec.arity == args::Arity::Many));
+  match spec.map(|spec| spec.kind) {
+    Some(ArgKind::Choice(choices)) => candidates.extend_from_slice(choices),
+    Some(ArgKind::Command) => candidates.extend(registry::commands().iter().map(|command| command.name)),
+    Some(ArgKind::Address) => candidates.extend(registers::all().map(|register| register.name)),
+    _ => {}
+  }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Commands the shell knows, registered by the subsystems that implement them.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt;
+
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use super::args::{ArgError, ArgSpec, Args, Arity};
+use crate::sync::SpinLock;
+
+/// Why a command failed.
+#[derive(Clone, Debug)]
+pub enum CommandError {
+  /// The arguments didn't match the command's [ArgSpec]s
+  Args(ArgError),
+  /// The command ran, but couldn't do what it was asked to
+  Failed(String),
+  /// There's no command with this name
+  UnknownCommand(String),
+  /// The command finished with this exit status, having already said why if it had anything to say
+  Exit(u8),
+}
+
+impl CommandError {
+  /// Exit status of a command that failed like this, the same as a Unix shell would use.
+  pub fn status(&self) -> u8 {
+    match self {
+      CommandError::Args(_) => 2,
+      CommandError::Failed(_) => 1,
+      CommandError::UnknownCommand(_) => 127,
+      CommandError::Exit(status) => *status,
+    }
+  }
+}
+
+impl From<ArgError> for CommandError {
+  fn from(error: ArgError) -> Self {
+    Self::Args(error)
+  }
+}
+
+pub type CommandResult = Result<(), CommandError>;
+
+/// A shell command.
+///
+/// ```ignore
+/// const COMMANDS: &[Command] = &[Command {
+///   name: "sleep",
+///   summary: "waits for a while",
+///   help: "",
+///   args: &[ArgSpec::required("time", ArgKind::Duration, "how long to wait")],
+///   run: |args| { ...; Ok(()) },
+/// }];
+/// shell::register(COMMANDS);
+/// ```
+pub struct Command {
+  pub name: &'static str,
+  /// One line description, shown in the command list
+  pub summary: &'static str,
+  /// Longer description for `help <command>`, may be empty
+  pub help: &'static str,
+  pub args: &'static [ArgSpec],
+  pub run: fn(&Args) -> CommandResult,
+}
+
+impl Command {
+  /// Formats the command with its arguments, e.g. `loglevel [level]`.
+  pub fn usage(&self) -> Usage<'_> {
+    Usage(self)
+  }
+}
+
+/// See [Command::usage].
+pub struct Usage<'a>(&'a Command);
+
+impl fmt::Display for Usage<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0.name)?;
+    for arg in self.0.args {
+      match arg.arity {
+        Arity::Required => write!(f, " <{}>", arg.name)?,
+        Arity::Optional => write!(f, " [{}]", arg.name)?,
+        Arity::Many => write!(f, " [{}...]", arg.name)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+static REGISTRY: SpinLock<Vec<&'static Command>> = SpinLock::new(Vec::new());
+
+/// Makes `commands` available in the shell. A command with the name of one that's already registered is skipped.
+pub fn register(commands: &'static [Command]) {
+  for command in commands {
+    debug_assert!(
+      command.args.iter().rev().skip(1).all(|arg| arg.arity != Arity::Many),
+      "Only the last argument can take many values",
+    );
+    let added = REGISTRY.with(|registry| match registry.binary_search_by(|other| other.name.cmp(command.name)) {
+      Ok(_) => false,
+      Err(index) => {
+        registry.insert(index, command);
+        true
+      }
+    });
+    if !added {
+      warn!("Shell command \"{}\" is already registered", command.name);
+    }
+  }
+}
+
+/// The registered command called `name`.
+pub fn find(name: &str) -> Option<&'static Command> {
+  REGISTRY.with(|registry| {
+    registry.binary_search_by(|command| command.name.cmp(name)).ok().map(|index| registry[index])
+  })
+}
+
+/// Every registered command, sorted by name.
+pub fn commands() -> Vec<&'static Command> {
+  REGISTRY.with(|registry| registry.clone())
+}
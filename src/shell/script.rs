@@ -0,0 +1,309 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell scripts, which are what the shell runs whether the lines come from the terminal or from a script.
+//!
+//! ```text
+//! # Comments start with a hash
+//! set COUNT 3
+//! repeat $COUNT
+//!   echo tick; sleep 1s
+//! end
+//! if test $? = 0
+//!   echo done
+//! else
+//!   echo interrupted || true
+//! end
+//! ```
+//!
+//! - `;` separates commands, `a && b` runs `b` if `a` succeeded, `a || b` runs `b` if `a` failed
+//! - `if <commands>` runs the lines up to `else` or `end` if the commands succeeded, and the ones after `else` otherwise
+//! - `repeat <count>` runs the lines up to `end` `count` times
+//! - Ctrl-C stops the script, see [interrupted]
+//!
+//! Pipes aren't supported, commands print straight to the console and don't read any input.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use rust_alloc::string::String;
+use rust_alloc::vec::Vec;
+
+use super::args::{self, parse_integer};
+use super::env;
+use crate::console;
+
+/// How deep scripts may run other scripts.
+const MAX_DEPTH: usize = 8;
+/// Exit status of a command that was stopped with Ctrl-C.
+pub const INTERRUPTED_STATUS: u8 = 130;
+const CTRL_C: u8 = 0x03;
+
+/// A script built into the kernel.
+pub struct Script {
+  pub name: &'static str,
+  pub source: &'static str,
+}
+
+/// Scripts built into the kernel. `boot` is run when the shell starts.
+pub const SCRIPTS: &[Script] = &[Script { name: "boot", source: include_str!("scripts/boot.sh") }];
+
+pub fn find(name: &str) -> Option<&'static Script> {
+  SCRIPTS.iter().find(|script| script.name == name)
+}
+
+/// One statement of a script.
+#[derive(Clone, Debug)]
+pub enum Statement {
+  /// Commands separated by `;`, `&&` and `||`
+  Line(String),
+  If { condition: String, then: Vec<Statement>, otherwise: Vec<Statement> },
+  /// The count is expanded when the statement runs, so it can be a variable
+  Repeat { count: String, body: Vec<Statement> },
+}
+
+#[derive(Clone, Debug)]
+pub enum ParseError {
+  /// The block starting on this line wasn't closed with `end`
+  Unterminated { line: usize, keyword: &'static str },
+  /// An `else` or `end` that doesn't belong to a block
+  Unexpected { line: usize, keyword: &'static str },
+  /// An `if` or `repeat` without anything after it
+  MissingOperand { line: usize, keyword: &'static str },
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseError::Unterminated { line, keyword } => write!(f, "line {}: '{}' without 'end'", line, keyword),
+      ParseError::Unexpected { line, keyword } => write!(f, "line {}: unexpected '{}'", line, keyword),
+      ParseError::MissingOperand { line, keyword } => write!(f, "line {}: nothing after '{}'", line, keyword),
+    }
+  }
+}
+
+/// What ended a block.
+enum Terminator {
+  Else(usize),
+  End(usize),
+  Eof,
+}
+
+/// Parses a script into statements.
+pub fn parse(source: &str) -> Result<Vec<Statement>, ParseError> {
+  let mut lines = source.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+  match parse_block(&mut lines)? {
+    (statements, Terminator::Eof) => Ok(statements),
+    (_, Terminator::Else(line)) => Err(ParseError::Unexpected { line, keyword: "else" }),
+    (_, Terminator::End(line)) => Err(ParseError::Unexpected { line, keyword: "end" }),
+  }
+}
+
+/// Whether `source` has a block that isn't closed yet, so more lines should be read before running it.
+pub fn is_incomplete(source: &str) -> bool {
+  matches!(parse(source), Err(ParseError::Unterminated { .. }))
+}
+
+fn parse_block<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>) -> Result<(Vec<Statement>, Terminator), ParseError> {
+  let mut statements = Vec::new();
+
+  while let Some((number, line)) = lines.next() {
+    let (keyword, rest) = line.split_once(char::is_whitespace).map_or((line, ""), |(keyword, rest)| (keyword, rest.trim_start()));
+    match keyword {
+      "" => {}
+      _ if keyword.starts_with('#') => {}
+      "else" => return Ok((statements, Terminator::Else(number))),
+      "end" => return Ok((statements, Terminator::End(number))),
+      "if" => {
+        if rest.is_empty() {
+          return Err(ParseError::MissingOperand { line: number, keyword: "if" });
+        }
+        let unterminated = ParseError::Unterminated { line: number, keyword: "if" };
+        let (then, terminator) = parse_block(lines)?;
+        let otherwise = match terminator {
+          Terminator::Else(_) => match parse_block(lines)? {
+            (otherwise, Terminator::End(_)) => otherwise,
+            (_, Terminator::Else(line)) => return Err(ParseError::Unexpected { line, keyword: "else" }),
+            (_, Terminator::Eof) => return Err(unterminated),
+          },
+          Terminator::End(_) => Vec::new(),
+          Terminator::Eof => return Err(unterminated),
+        };
+        statements.push(Statement::If { condition: rest.into(), then, otherwise });
+      }
+      "repeat" => {
+        if rest.is_empty() {
+          return Err(ParseError::MissingOperand { line: number, keyword: "repeat" });
+        }
+        match parse_block(lines)? {
+          (body, Terminator::End(_)) => statements.push(Statement::Repeat { count: rest.into(), body }),
+          (_, Terminator::Else(line)) => return Err(ParseError::Unexpected { line, keyword: "else" }),
+          (_, Terminator::Eof) => return Err(ParseError::Unterminated { line: number, keyword: "repeat" }),
+        }
+      }
+      _ => statements.push(Statement::Line(line.into())),
+    }
+  }
+  Ok((statements, Terminator::Eof))
+}
+
+/// How a command is joined to the one before it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Connector {
+  /// First command, or after `;`
+  Always,
+  /// After `&&`
+  And,
+  /// After `||`
+  Or,
+}
+
+/// Splits a line at `;`, `&&` and `||` outside of quotes, dropping a `#` comment at the end.
+fn split_chain(line: &str) -> Result<Vec<(Connector, &str)>, &'static str> {
+  let mut commands = Vec::new();
+  let mut connector = Connector::Always;
+  let mut start = 0;
+  let mut end = line.len();
+  let mut quote = None;
+  let mut chars = line.char_indices().peekable();
+
+  while let Some((i, c)) = chars.next() {
+    match (quote, c) {
+      (Some(q), c) if c == q => quote = None,
+      (Some('"'), '\\') => {
+        chars.next();
+      }
+      (Some(_), _) => {}
+      (None, '\'' | '"') => quote = Some(c),
+      (None, '\\') => {
+        chars.next();
+      }
+      (None, '#') if line[..i].chars().next_back().is_none_or(|c| c.is_whitespace() || c == ';') => {
+        end = i;
+        break;
+      }
+      (None, ';') => {
+        commands.push((connector, line[start..i].trim()));
+        connector = Connector::Always;
+        start = i + 1;
+      }
+      (None, '&' | '|') if chars.peek().is_some_and(|&(_, next)| next == c) => {
+        chars.next();
+        commands.push((connector, line[start..i].trim()));
+        connector = if c == '&' { Connector::And } else { Connector::Or };
+        start = i + 2;
+      }
+      (None, '|') => return Err("pipes aren't supported"),
+      _ => {}
+    }
+  }
+  commands.push((connector, line[start..end].trim()));
+
+  // Empty commands are fine around `;`, but `&&` and `||` need a command on both sides.
+  for (i, &(connector, command)) in commands.iter().enumerate() {
+    let next = commands.get(i + 1).map_or(Connector::Always, |&(connector, _)| connector);
+    if command.is_empty() && (connector != Connector::Always || next != Connector::Always) {
+      return Err("'&&' and '||' need a command on both sides");
+    }
+  }
+  commands.retain(|(_, command)| !command.is_empty());
+  Ok(commands)
+}
+
+static DEPTH: AtomicUsize = AtomicUsize::new(0);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether Ctrl-C has been pressed since the outermost script started. Long running commands should check this now and
+/// then, and stop with [INTERRUPTED_STATUS] if it's set.
+///
+/// Anything else typed while a script runs is thrown away.
+pub fn interrupted() -> bool {
+  if console::try_read_byte() == Some(CTRL_C) {
+    INTERRUPTED.store(true, Ordering::Relaxed);
+  }
+  INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Runs a script, reporting errors on the console. Returns the exit status of the last command that ran, or
+/// `None` if the script couldn't run because scripts are already nested too deeply.
+pub fn run(source: &str) -> Option<u8> {
+  let depth = DEPTH.fetch_add(1, Ordering::Relaxed);
+  if depth >= MAX_DEPTH {
+    DEPTH.fetch_sub(1, Ordering::Relaxed);
+    return None;
+  }
+  if depth == 0 {
+    INTERRUPTED.store(false, Ordering::Relaxed);
+  }
+
+  match parse(source) {
+    Ok(statements) => execute(&statements),
+    Err(error) => {
+      println!("{}", error);
+      env::set_last_status(2);
+    }
+  }
+  if depth == 0 && INTERRUPTED.load(Ordering::Relaxed) {
+    println!("Interrupted");
+  }
+  DEPTH.fetch_sub(1, Ordering::Relaxed);
+  Some(env::last_status())
+}
+
+fn execute(statements: &[Statement]) {
+  for statement in statements {
+    if interrupted() {
+      env::set_last_status(INTERRUPTED_STATUS);
+      return;
+    }
+    match statement {
+      Statement::Line(line) => run_chain(line),
+      Statement::If { condition, then, otherwise } => {
+        run_chain(condition);
+        if !interrupted() {
+          execute(if env::last_status() == 0 { then } else { otherwise });
+        }
+      }
+      Statement::Repeat { count, body } => {
+        let words = args::tokenize(count, env::get).unwrap_or_default();
+        let Some(count) = words.first().filter(|_| words.len() == 1).and_then(|word| parse_integer(word)) else {
+          println!("repeat: expected a count, got \"{}\"", count);
+          env::set_last_status(2);
+          continue;
+        };
+        for _ in 0..count {
+          if interrupted() {
+            break;
+          }
+          execute(body);
+        }
+      }
+    }
+  }
+}
+
+fn run_chain(line: &str) {
+  let commands = match split_chain(line) {
+    Ok(commands) => commands,
+    Err(message) => {
+      println!("{}", message);
+      env::set_last_status(2);
+      return;
+    }
+  };
+  for (connector, command) in commands {
+    let skip = match connector {
+      Connector::Always => false,
+      Connector::And => env::last_status() != 0,
+      Connector::Or => env::last_status() == 0,
+    };
+    if skip {
+      continue;
+    }
+    if interrupted() {
+      env::set_last_status(INTERRUPTED_STATUS);
+      return;
+    }
+    // Errors have been reported already.
+    let _ = super::run_line(command);
+  }
+}
@@ -0,0 +1,5 @@
+# Run by the shell when it starts, before the first prompt. See 'help run' for the syntax.
+
+# The prompt shown before every command
+set PS1 '$ '
+echo "Type 'help' for a list of commands."
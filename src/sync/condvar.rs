@@ -0,0 +1,66 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! A condition variable, for waiting until the value behind a [Mutex] changes.
+
+use rust_alloc::collections::VecDeque;
+
+use super::{MutexGuard, SpinLock};
+use crate::thread::{self, ThreadId};
+
+/// Lets threads wait until another thread changes the value behind a [super::Mutex] and notifies them.
+///
+/// ```ignore
+/// let mut queue = QUEUE.lock();
+/// queue = READY.wait_while(queue, |queue| queue.is_empty());
+/// ```
+///
+/// Like with `std::sync::Condvar`, [Condvar::wait] may return without a notification, so the condition should be
+/// checked again, which [Condvar::wait_while] does.
+pub struct Condvar {
+  waiters: SpinLock<VecDeque<ThreadId>>,
+}
+
+impl Condvar {
+  pub const fn new() -> Self {
+    Condvar { waiters: SpinLock::new(VecDeque::new()) }
+  }
+
+  /// Unlocks the mutex, waits for a notification, and locks the mutex again.
+  pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    let mutex = guard.mutex();
+    // Queued before unlocking, so a notification right after the unlock isn't missed. If it comes before the thread
+    // parks, [thread::park] returns immediately.
+    self.waiters.with(super::enqueue_current);
+    drop(guard);
+    thread::park();
+    let current = thread::current();
+    self.waiters.with(|waiters| waiters.retain(|&id| id != current));
+    mutex.lock()
+  }
+
+  /// Waits until `condition` returns false, checking it with the mutex locked.
+  pub fn wait_while<'a, T>(&self, mut guard: MutexGuard<'a, T>, mut condition: impl FnMut(&mut T) -> bool) -> MutexGuard<'a, T> {
+    while condition(&mut guard) {
+      guard = self.wait(guard);
+    }
+    guard
+  }
+
+  /// Wakes the thread that has been waiting the longest.
+  pub fn notify_one(&self) {
+    if let Some(waiter) = self.waiters.with(|waiters| waiters.pop_front()) {
+      thread::unpark(waiter);
+    }
+  }
+
+  pub fn notify_all(&self) {
+    for waiter in self.waiters.with(core::mem::take) {
+      thread::unpark(waiter);
+    }
+  }
+}
+
+impl Default for Condvar {
+  fn default() -> Self {
+    Self::new()
+  }
+}
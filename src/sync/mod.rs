@@ -0,0 +1,31 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Synchronization primitives for code shared between threads and IRQ handlers.
+//!
+//! - [SpinLock] masks interrupts while it's held, and may be used anywhere, including IRQ handlers
+//! - [Mutex], [Semaphore] and [Condvar] put the waiting thread to sleep, so they're only for threads
+//! - [OnceCell] and [Lazy] initialize a value on first use
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+mod condvar;
+mod mutex;
+mod once;
+mod semaphore;
+mod spin;
+
+pub use self::condvar::Condvar;
+pub use self::mutex::{Mutex, MutexGuard};
+pub use self::once::{Lazy, OnceCell};
+pub use self::semaphore::Semaphore;
+pub use self::spin::{SpinLock, SpinLockGuard};
+
+use rust_alloc::collections::VecDeque;
+
+use crate::thread::{self, ThreadId};
+
+/// Queues the current thread in `waiters`, unless it's there already after waking up spuriously.
+fn enqueue_current(waiters: &mut VecDeque<ThreadId>) {
+  let current = thread::current();
+  if !waiters.contains(&current) {
+    waiters.push_back(current);
+  }
+}
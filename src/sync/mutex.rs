@@ -0,0 +1,126 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! A lock that puts threads waiting for it to sleep.
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use rust_alloc::collections::VecDeque;
+
+use super::SpinLock;
+use crate::thread::{self, ThreadId};
+
+struct State {
+  locked: bool,
+  /// Threads waiting for the lock, woken one at a time in order
+  waiters: VecDeque<ThreadId>,
+}
+
+/// Protects a value that may be held for a while. Threads waiting for it are parked, and interrupts stay enabled.
+///
+/// Must not be used from IRQ handlers, or before [thread::init] if it could be contended. See [SpinLock] for those.
+pub struct Mutex<T> {
+  state: SpinLock<State>,
+  value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only reachable through a guard, of which there is only one at a time.
+unsafe impl<T: Send> Sync for Mutex<T> {}
+unsafe impl<T: Send> Send for Mutex<T> {}
+
+impl<T> Mutex<T> {
+  pub const fn new(value: T) -> Self {
+    Mutex { state: SpinLock::new(State { locked: false, waiters: VecDeque::new() }), value: UnsafeCell::new(value) }
+  }
+
+  /// Waits until the lock is free and takes it.
+  pub fn lock(&self) -> MutexGuard<'_, T> {
+    loop {
+      let taken = self.state.with(|state| {
+        if !state.locked {
+          state.locked = true;
+          return true;
+        }
+        super::enqueue_current(&mut state.waiters);
+        false
+      });
+      if taken {
+        return MutexGuard { mutex: self, _not_send: PhantomData };
+      }
+      thread::park();
+    }
+  }
+
+  /// Takes the lock if it's free, without waiting.
+  pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+    self.state.with(|state| !core::mem::replace(&mut state.locked, true)).then(|| MutexGuard { mutex: self, _not_send: PhantomData })
+  }
+
+  pub fn is_locked(&self) -> bool {
+    self.state.with(|state| state.locked)
+  }
+
+  /// Runs `f` with the lock held.
+  pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+    f(&mut self.lock())
+  }
+
+  pub fn get_mut(&mut self) -> &mut T {
+    self.value.get_mut()
+  }
+
+  pub fn into_inner(self) -> T {
+    self.value.into_inner()
+  }
+
+  fn unlock(&self) {
+    let waiter = self.state.with(|state| {
+      state.locked = false;
+      state.waiters.pop_front()
+    });
+    // The woken thread competes for the lock like any other, it queues up again if it loses.
+    if let Some(waiter) = waiter {
+      thread::unpark(waiter);
+    }
+  }
+}
+
+/// Access to the value of a [Mutex], unlocking it when dropped.
+///
+/// The guard can't be sent to another thread, the thread that locked the mutex has to be the one to unlock it.
+pub struct MutexGuard<'a, T> {
+  mutex: &'a Mutex<T>,
+  _not_send: PhantomData<*const ()>,
+}
+
+// SAFETY: Sharing the guard only shares `&T`.
+unsafe impl<T: Sync> Sync for MutexGuard<'_, T> {}
+
+impl<'a, T> MutexGuard<'a, T> {
+  /// The mutex this guard locks, for [super::Condvar] to take it again.
+  pub(in crate::sync) fn mutex(&self) -> &'a Mutex<T> {
+    self.mutex
+  }
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    // SAFETY: The guard holds the lock.
+    unsafe { &*self.mutex.value.get() }
+  }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+  fn deref_mut(&mut self) -> &mut T {
+    // SAFETY: The guard holds the lock.
+    unsafe { &mut *self.mutex.value.get() }
+  }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+  fn drop(&mut self) {
+    self.mutex.unlock();
+  }
+}
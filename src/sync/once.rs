@@ -0,0 +1,126 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Values initialized once, on first use.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+use crate::thread;
+use crate::util::cpu;
+
+const EMPTY: u8 = 0;
+const INITIALIZING: u8 = 1;
+const READY: u8 = 2;
+/// [OnceCell::initializer] when the value was being initialized before [thread::init]
+const NO_THREAD: u32 = u32::MAX;
+
+/// A value that is set once and then only read, like `std::sync::OnceLock`.
+///
+/// A thread that finds another thread initializing the value yields until it's done. Initializing it from an IRQ
+/// handler that interrupted the initialization, or recursively from the initializer, panics.
+pub struct OnceCell<T> {
+  state: AtomicU8,
+  /// Id of the thread running the initializer, to tell recursion apart from waiting for another thread
+  initializer: AtomicU32,
+  value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: The value is only written once, by whoever moved the state to INITIALIZING, and only read once it's READY.
+unsafe impl<T: Send + Sync> Sync for OnceCell<T> {}
+unsafe impl<T: Send> Send for OnceCell<T> {}
+
+impl<T> OnceCell<T> {
+  pub const fn new() -> Self {
+    OnceCell { state: AtomicU8::new(EMPTY), initializer: AtomicU32::new(NO_THREAD), value: UnsafeCell::new(MaybeUninit::uninit()) }
+  }
+
+  pub fn get(&self) -> Option<&T> {
+    if self.state.load(Ordering::Acquire) != READY {
+      return None;
+    }
+    // SAFETY: The value is initialized once the state is READY, and isn't written anymore.
+    Some(unsafe { (*self.value.get()).assume_init_ref() })
+  }
+
+  /// Sets the value, or gives it back if there already is one.
+  pub fn set(&self, value: T) -> Result<(), T> {
+    let mut value = Some(value);
+    self.get_or_init(|| value.take().expect("Initializer runs once"));
+    value.map_or(Ok(()), Err)
+  }
+
+  /// The value, calling `f` to initialize it if there isn't one yet.
+  pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+    loop {
+      match self.state.compare_exchange(EMPTY, INITIALIZING, Ordering::Acquire, Ordering::Acquire) {
+        Ok(_) => {
+          let current = if thread::is_initialized() { thread::current().as_u32() } else { NO_THREAD };
+          self.initializer.store(current, Ordering::Relaxed);
+          // SAFETY: Moving the state to INITIALIZING grants exclusive access to the value.
+          unsafe { (*self.value.get()).write(f()) };
+          self.state.store(READY, Ordering::Release);
+        }
+        Err(INITIALIZING) => {
+          // A thread that isn't running is initializing the value, it can only continue if this one gives way.
+          // With IRQs masked, this is an IRQ handler or holds a SpinLock, and can't switch threads.
+          if !thread::is_initialized() || !cpu::irqs_enabled() {
+            panic!("OnceCell initialized recursively or from an IRQ handler");
+          }
+          if self.initializer.load(Ordering::Relaxed) == thread::current().as_u32() {
+            panic!("OnceCell initialized recursively");
+          }
+          thread::yield_now();
+          continue;
+        }
+        Err(_) => {}
+      }
+      // SAFETY: The state is READY.
+      return unsafe { (*self.value.get()).assume_init_ref() };
+    }
+  }
+}
+
+impl<T> Default for OnceCell<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Drop for OnceCell<T> {
+  fn drop(&mut self) {
+    if *self.state.get_mut() == READY {
+      // SAFETY: The value is initialized, and nothing can be using it anymore.
+      unsafe { self.value.get_mut().assume_init_drop() };
+    }
+  }
+}
+
+/// A value computed by `F` the first time it's used, like `std::sync::LazyLock`.
+///
+/// ```ignore
+/// static TABLE: Lazy<Vec<u32>> = Lazy::new(|| (0..256).map(crc_entry).collect());
+/// ```
+pub struct Lazy<T, F = fn() -> T> {
+  cell: OnceCell<T>,
+  init: F,
+}
+
+impl<T, F: Fn() -> T> Lazy<T, F> {
+  pub const fn new(init: F) -> Self {
+    Lazy { cell: OnceCell::new(), init }
+  }
+
+  /// Computes the value now, if it hasn't been yet.
+  pub fn force(this: &Self) -> &T {
+    this.cell.get_or_init(&this.init)
+  }
+}
+
+impl<T, F: Fn() -> T> Deref for Lazy<T, F> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    Lazy::force(self)
+  }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! A counting semaphore.
+
+use rust_alloc::collections::VecDeque;
+
+use super::SpinLock;
+use crate::thread::{self, ThreadId};
+
+struct State {
+  permits: u32,
+  waiters: VecDeque<ThreadId>,
+}
+
+/// Hands out a number of permits, parking threads that want one while there are none left.
+///
+/// [Semaphore::release] may be called from IRQ handlers, e.g. to tell a thread that data has arrived.
+/// [Semaphore::acquire] is only for threads.
+pub struct Semaphore {
+  state: SpinLock<State>,
+}
+
+impl Semaphore {
+  pub const fn new(permits: u32) -> Self {
+    Semaphore { state: SpinLock::new(State { permits, waiters: VecDeque::new() }) }
+  }
+
+  /// Waits until a permit is available and takes it.
+  pub fn acquire(&self) {
+    loop {
+      let acquired = self.state.with(|state| {
+        if state.permits > 0 {
+          state.permits -= 1;
+          return true;
+        }
+        super::enqueue_current(&mut state.waiters);
+        false
+      });
+      if acquired {
+        return;
+      }
+      thread::park();
+    }
+  }
+
+  /// Takes a permit if one is available, without waiting.
+  pub fn try_acquire(&self) -> bool {
+    self.state.with(|state| {
+      let acquired = state.permits > 0;
+      if acquired {
+        state.permits -= 1;
+      }
+      acquired
+    })
+  }
+
+  /// Returns a permit, waking a thread waiting for one.
+  pub fn release(&self) {
+    let waiter = self.state.with(|state| {
+      state.permits = state.permits.saturating_add(1);
+      state.waiters.pop_front()
+    });
+    if let Some(waiter) = waiter {
+      thread::unpark(waiter);
+    }
+  }
+
+  pub fn available(&self) -> u32 {
+    self.state.with(|state| state.permits)
+  }
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! A lock that masks interrupts while it's held.
+
+use core::arch::asm;
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::util::cpu::{self, IrqState};
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+
+/// Protects a value by masking IRQs and FIQs, and taking a lock word with `ldrex`/`strex` for cores that don't share
+/// the mask. The guard unmasks interrupts again if they were enabled when it was taken.
+///
+/// With interrupts masked nothing can preempt the holder, so it should only be held briefly, and never across anything
+/// that sleeps or switches threads. The Pi Zero has a single core, so the lock being taken already can only mean it's
+/// being taken recursively, which spins forever.
+pub struct SpinLock<T> {
+  state: AtomicU32,
+  value: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only reachable through a guard, of which there is only one at a time.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+unsafe impl<T: Send> Send for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+  pub const fn new(value: T) -> Self {
+    SpinLock { state: AtomicU32::new(UNLOCKED), value: UnsafeCell::new(value) }
+  }
+
+  pub fn lock(&self) -> SpinLockGuard<'_, T> {
+    let irq_state = cpu::interrupts_save();
+    while !self.acquire() {
+      core::hint::spin_loop();
+    }
+    SpinLockGuard { lock: self, irq_state, _not_send: PhantomData }
+  }
+
+  /// Takes the lock if it isn't held, without waiting.
+  pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+    let irq_state = cpu::interrupts_save();
+    if self.acquire() {
+      return Some(SpinLockGuard { lock: self, irq_state, _not_send: PhantomData });
+    }
+    cpu::interrupts_restore(irq_state);
+    None
+  }
+
+  /// Runs `f` with the lock held.
+  pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+    f(&mut self.lock())
+  }
+
+  pub fn is_locked(&self) -> bool {
+    self.state.load(Ordering::Relaxed) != UNLOCKED
+  }
+
+  /// Mutable access without locking, which the borrow checker already guarantees to be exclusive.
+  pub fn get_mut(&mut self) -> &mut T {
+    self.value.get_mut()
+  }
+
+  /// Tries to change the lock word from unlocked to locked once. Fails if it's locked, or if the exclusive access was
+  /// lost to another core or an exception in the meantime.
+  fn acquire(&self) -> bool {
+    let failed: u32;
+    // SAFETY: Only accesses the lock word, which is a valid, aligned u32.
+    unsafe {
+      asm!(
+        "ldrex {failed}, [{state}]",
+        "cmp {failed}, #{unlocked}",
+        // Sets `failed` to 0 if the store went through. If the lock was held, `failed` keeps the nonzero state.
+        "strexeq {failed}, {locked}, [{state}]",
+        failed = out(reg) failed,
+        state = in(reg) self.state.as_ptr(),
+        locked = in(reg) LOCKED,
+        unlocked = const UNLOCKED,
+        options(nostack),
+      );
+    }
+    if failed != 0 {
+      return false;
+    }
+    // Nothing protected by the lock may be accessed before it's taken.
+    cpu::data_memory_barrier();
+    true
+  }
+
+  fn release(&self) {
+    // Everything done under the lock must be visible before it's released.
+    cpu::data_memory_barrier();
+    self.state.store(UNLOCKED, Ordering::Release);
+  }
+}
+
+/// Access to the value of a [SpinLock], unlocking it and restoring the interrupt mask when dropped.
+///
+/// The guard can't be sent to another thread, since dropping it there would restore this thread's interrupt mask.
+pub struct SpinLockGuard<'a, T> {
+  lock: &'a SpinLock<T>,
+  irq_state: IrqState,
+  _not_send: PhantomData<*const ()>,
+}
+
+// SAFETY: Sharing the guard only shares `&T`.
+unsafe impl<T: Sync> Sync for SpinLockGuard<'_, T> {}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    // SAFETY: The guard holds the lock.
+    unsafe { &*self.lock.value.get() }
+  }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+  fn deref_mut(&mut self) -> &mut T {
+    // SAFETY: The guard holds the lock.
+    unsafe { &mut *self.lock.value.get() }
+  }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+  fn drop(&mut self) {
+    self.lock.release();
+    cpu::interrupts_restore(self.irq_state);
+  }
+}
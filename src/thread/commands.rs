@@ -0,0 +1,37 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Shell commands reporting on threads.
+
+use rust_alloc::format;
+use rust_alloc::string::String;
+
+use super::ThreadState;
+use crate::shell::{Args, Command, CommandResult};
+use crate::time::Instant;
+
+pub const COMMANDS: &[Command] = &[
+  Command {
+    name: "threads",
+    summary: "lists the kernel threads",
+    help: "Threads that have finished are listed until they're joined.",
+    args: &[],
+    run: threads,
+  },
+];
+
+fn threads(_: &Args) -> CommandResult {
+  let current = super::current();
+  let now = Instant::now();
+  println!("{:>4}  {:<12} {:<16} {:>9} {:>10}", "ID", "NAME", "STATE", "STACK", "SWITCHES");
+  for thread in super::threads() {
+    let state: String = match thread.state {
+      ThreadState::Sleeping(deadline) => format!("sleeping {}ms", deadline.duration_since(now).as_millis()),
+      state => state.name().into(),
+    };
+    let stack = if thread.stack_size == 0 { "boot".into() } else { format!("{}", thread.stack_size) };
+    println!(
+      "{:>4}{} {:<12} {:<16} {:>9} {:>10}",
+      thread.id, if thread.id == current { "*" } else { " " }, thread.name, state, stack, thread.switches,
+    );
+  }
+  Ok(())
+}
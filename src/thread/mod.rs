@@ -0,0 +1,530 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Kernel threads, scheduled round-robin and preempted by the ARM timer.
+//!
+//! The thread that calls [init] becomes `main`, running on the boot stack. Other threads get a stack from the heap.
+//! When no thread is ready to run, the `idle` thread waits for an interrupt.
+//!
+//! ```ignore
+//! let worker = thread::spawn(|| (1..=10).sum::<u32>(), 16 * 1024);
+//! thread::sleep(Duration::from_millis(100));
+//! assert_eq!(worker.join(), 55);
+//! ```
+//!
+//! Threads are switched in `switch.s`, saving the callee-saved registers and the whole VFP state on the thread's
+//! stack. A thread is preempted at the end of the IRQ that ended its time slice, on top of the IRQ's frame.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use rust_alloc::boxed::Box;
+use rust_alloc::collections::{BTreeMap, VecDeque};
+use rust_alloc::sync::Arc;
+use rust_alloc::vec::Vec;
+
+use crate::exception::{self, ExceptionContext, ExceptionKind};
+use crate::peripheral::drivers::arm_timer;
+use crate::peripheral::drivers::interrupt::{self, constants::IrqSource};
+use crate::sync::SpinLock;
+use crate::time::{self, timers, Duration, Instant};
+use crate::util::cpu;
+
+pub mod commands;
+
+core::arch::global_asm!(include_str!("switch.s"), options(raw));
+
+unsafe extern "C" {
+  /// Saves the current thread's state on its stack and its stack pointer to `save_sp`, then resumes the thread that
+  /// saved `sp`. Returns when something switches back to the current thread.
+  fn thread_switch(save_sp: *mut u32, sp: u32);
+  /// First code a new thread runs, see [SwitchFrame::new].
+  fn thread_trampoline();
+}
+
+/// How long a thread runs before another ready thread gets a turn.
+pub const TIME_SLICE: Duration = Duration::from_millis(10);
+/// Smallest stack [spawn] hands out. Smaller sizes are rounded up to this.
+///
+/// IRQs are handled on the stack of the thread they interrupt, so on top of what the thread itself uses, every stack
+/// needs room for the exception frame, the IRQ handlers (which may log) and a switch to another thread.
+/// The canary only catches an overflow once the thread is switched out, after it has overwritten whatever is below.
+pub const MIN_STACK_SIZE: usize = 8 * 1024;
+/// Stack size of the idle thread, which only waits for interrupts and handles them.
+const IDLE_STACK_SIZE: usize = MIN_STACK_SIZE;
+/// Written at the bottom of every heap allocated stack, and checked whenever its thread is switched out.
+const STACK_CANARY: u64 = 0x57AC_C0DE_DEAD_BEEF;
+
+/// Identifies a thread. Ids aren't reused.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ThreadId(u32);
+
+impl ThreadId {
+  pub fn as_u32(&self) -> u32 {
+    self.0
+  }
+}
+
+impl fmt::Display for ThreadId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ThreadState {
+  Running,
+  /// Waiting for its turn
+  Ready,
+  /// In [sleep], until the instant
+  Sleeping(Instant),
+  /// In [park], until [unpark]ed
+  Parked,
+  /// Returned from its function, waiting to be joined
+  Finished,
+}
+
+impl ThreadState {
+  pub fn name(&self) -> &'static str {
+    match self {
+      ThreadState::Running => "running",
+      ThreadState::Ready => "ready",
+      ThreadState::Sleeping(_) => "sleeping",
+      ThreadState::Parked => "parked",
+      ThreadState::Finished => "finished",
+    }
+  }
+}
+
+/// What [thread_switch] leaves on the stack of a thread that isn't running, lowest address first.
+#[repr(C)]
+struct SwitchFrame {
+  fpscr: u32,
+  _padding: u32,
+  d: [u64; 16],
+  /// r4-r12, r12 is only there for alignment
+  r: [u32; 9],
+  lr: u32,
+}
+
+// Sanity checks. Compile-time assertions, doesn't create any extra runtime code.
+const _: () = assert!(core::mem::size_of::<SwitchFrame>() == 176, "SwitchFrame must match the frame built in switch.s");
+const _: () = assert!(core::mem::size_of::<SwitchFrame>().is_multiple_of(8), "Stacks must stay 8 byte aligned");
+const _: () = assert!(MIN_STACK_SIZE > core::mem::size_of::<SwitchFrame>(), "A new thread's frame must fit on its stack");
+
+impl SwitchFrame {
+  /// A frame that makes [thread_switch] continue in [thread_trampoline], which runs `entry`.
+  fn new(entry: Entry) -> Self {
+    let mut r = [0; 9];
+    // r4, taken over by [thread_start].
+    r[0] = Box::into_raw(Box::new(entry)) as u32;
+    SwitchFrame { fpscr: 0, _padding: 0, d: [0; 16], r, lr: thread_trampoline as *const () as u32 }
+  }
+}
+
+/// Type of a new thread's function, as passed to [thread_trampoline].
+type Entry = Box<dyn FnOnce() + Send>;
+
+struct Thread {
+  name: &'static str,
+  state: ThreadState,
+  /// Saved stack pointer, only valid while the thread isn't running
+  sp: u32,
+  /// `None` for `main`, which runs on the boot stack
+  stack: Option<Vec<u64>>,
+  /// Set by [unpark] if the thread wasn't parked, so its next [park] returns immediately
+  unparked: bool,
+  /// Thread waiting in [JoinHandle::join]
+  joiner: Option<ThreadId>,
+  /// Whether the [JoinHandle] has been dropped, so nobody will join the thread
+  detached: bool,
+  /// Number of times the thread has been switched to
+  switches: u32,
+}
+
+impl Thread {
+  fn new(name: &'static str, state: ThreadState, stack: Option<Vec<u64>>) -> Self {
+    Thread { name, state, sp: 0, stack, unparked: false, joiner: None, detached: false, switches: 0 }
+  }
+
+  fn stack_size(&self) -> usize {
+    self.stack.as_ref().map_or(0, |stack| stack.len() * 8)
+  }
+
+  fn check_stack(&self, id: ThreadId) {
+    if let Some(stack) = &self.stack
+      && stack[0] != STACK_CANARY
+    {
+      panic!("Thread {} ({}) overflowed its {} byte stack", id, self.name, self.stack_size());
+    }
+  }
+}
+
+struct Scheduler {
+  threads: BTreeMap<ThreadId, Thread>,
+  /// Threads waiting for their turn, in order
+  ready: VecDeque<ThreadId>,
+  current: ThreadId,
+  idle: ThreadId,
+  /// Finished threads that nobody will join, freed once they've been switched out
+  zombies: Vec<ThreadId>,
+}
+
+/// Runs `f` on the scheduler. Panics if [init] hasn't been called.
+fn with_scheduler<R>(f: impl FnOnce(&mut Scheduler) -> R) -> R {
+  SCHEDULER.with(|scheduler| f(scheduler.as_mut().expect("Threads used before thread::init")))
+}
+
+static SCHEDULER: SpinLock<Option<Scheduler>> = SpinLock::new(None);
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+/// Set from IRQ handlers when another thread should run, acted on when the IRQ is done.
+static NEED_RESCHEDULE: AtomicBool = AtomicBool::new(false);
+
+fn next_id() -> ThreadId {
+  ThreadId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Allocates a stack of at least `size` bytes with `frame` on top, returning it and the stack pointer to resume it at.
+fn new_stack(size: usize, frame: SwitchFrame) -> (Vec<u64>, u32) {
+  let words = size.max(MIN_STACK_SIZE).div_ceil(8);
+  let mut stack = rust_alloc::vec![0u64; words];
+  stack[0] = STACK_CANARY;
+  let frame_words = core::mem::size_of::<SwitchFrame>() / 8;
+  let frame_address = stack[words - frame_words..].as_mut_ptr() as *mut SwitchFrame;
+  // SAFETY: The frame fits in the last words of the stack, which are 8 byte aligned like the frame.
+  unsafe { frame_address.write(frame) };
+  (stack, frame_address as u32)
+}
+
+/// Turns the calling code into the `main` thread, starts the idle thread and the time slice timer.
+/// Requires the heap, the interrupt controller and [timers] to be initialized, and IRQs still masked.
+pub fn init() {
+  cpu::vfp_enable();
+
+  let main = next_id();
+  let idle = next_id();
+  let (idle_stack, idle_sp) = new_stack(IDLE_STACK_SIZE, SwitchFrame::new(Box::new(idle_main)));
+  let mut threads = BTreeMap::new();
+  threads.insert(main, Thread::new("main", ThreadState::Running, None));
+  let mut idle_thread = Thread::new("idle", ThreadState::Ready, Some(idle_stack));
+  idle_thread.sp = idle_sp;
+  threads.insert(idle, idle_thread);
+
+  *SCHEDULER.lock() = Some(Scheduler { threads, ready: VecDeque::new(), current: main, idle, zombies: Vec::new() });
+  INITIALIZED.store(true, Ordering::Release);
+
+  // Pending IRQs are dispatched as before, with a chance to switch threads afterwards.
+  exception::register_handler(ExceptionKind::Irq, handle_irq);
+  interrupt::register_handler(IrqSource::ArmTimer, handle_tick);
+  interrupt::enable(IrqSource::ArmTimer);
+  if let Err(error) = arm_timer::arm_timer_start_periodic(TIME_SLICE) {
+    panic!("Can't start the time slice timer: {:?}", error);
+  }
+}
+
+/// Whether [init] has been called.
+pub fn is_initialized() -> bool {
+  INITIALIZED.load(Ordering::Acquire)
+}
+
+/// A thread started by [spawn], for waiting until it finishes and getting its result.
+/// Dropping it lets the thread finish on its own.
+pub struct JoinHandle<T> {
+  id: ThreadId,
+  result: Arc<ResultSlot<T>>,
+}
+
+/// Where a thread leaves the result of its function for [JoinHandle::join].
+struct ResultSlot<T>(UnsafeCell<Option<T>>);
+
+// SAFETY: The slot is written once by the thread just before it finishes, and only read by the joiner after that.
+unsafe impl<T: Send> Sync for ResultSlot<T> {}
+
+impl<T> JoinHandle<T> {
+  pub fn id(&self) -> ThreadId {
+    self.id
+  }
+
+  pub fn is_finished(&self) -> bool {
+    with_scheduler(|scheduler| scheduler.threads.get(&self.id).is_none_or(|thread| thread.state == ThreadState::Finished))
+  }
+
+  /// Waits for the thread to finish, and returns what its function returned.
+  pub fn join(self) -> T {
+    loop {
+      let finished = with_scheduler(|scheduler| {
+        let current = scheduler.current;
+        let thread = scheduler.threads.get_mut(&self.id).expect("Joined thread is gone");
+        if thread.state == ThreadState::Finished {
+          return true;
+        }
+        thread.joiner = Some(current);
+        false
+      });
+      if finished {
+        break;
+      }
+      park();
+    }
+    // Dropping the handle frees the finished thread.
+    // SAFETY: The thread has finished, so it's done writing the result.
+    unsafe { (*self.result.0.get()).take() }.expect("Finished thread left no result")
+  }
+}
+
+impl<T> Drop for JoinHandle<T> {
+  fn drop(&mut self) {
+    with_scheduler(|scheduler| {
+      let Some(thread) = scheduler.threads.get_mut(&self.id) else {
+        return;
+      };
+      // A finished thread has been switched out for good, so its stack can go. Otherwise [exit] leaves it to
+      // [after_switch].
+      if thread.state == ThreadState::Finished {
+        scheduler.threads.remove(&self.id);
+      } else {
+        thread.detached = true;
+      }
+    });
+  }
+}
+
+/// Starts a thread running `f` on a stack of `stack_size` bytes, at least [MIN_STACK_SIZE].
+/// The thread is queued behind the ready threads, the calling thread keeps running.
+pub fn spawn<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static, stack_size: usize) -> JoinHandle<T> {
+  spawn_named("thread", f, stack_size)
+}
+
+/// Like [spawn], with a name shown by the `threads` shell command.
+pub fn spawn_named<T: Send + 'static>(
+  name: &'static str,
+  f: impl FnOnce() -> T + Send + 'static,
+  stack_size: usize,
+) -> JoinHandle<T> {
+  let result = Arc::new(ResultSlot(UnsafeCell::new(None)));
+  let slot = result.clone();
+  let entry: Entry = Box::new(move || {
+    let value = f();
+    // SAFETY: Nothing reads the slot before the thread has finished.
+    unsafe { *slot.0.get() = Some(value) };
+  });
+  let (stack, sp) = new_stack(stack_size, SwitchFrame::new(entry));
+
+  let id = next_id();
+  let mut thread = Thread::new(name, ThreadState::Ready, Some(stack));
+  thread.sp = sp;
+  with_scheduler(|scheduler| {
+    scheduler.threads.insert(id, thread);
+    scheduler.ready.push_back(id);
+  });
+  JoinHandle { id, result }
+}
+
+/// Called by [thread_trampoline] with the thread's [Entry], on the thread's own stack.
+#[unsafe(no_mangle)]
+extern "C" fn thread_start(entry: *mut Entry) -> ! {
+  // SAFETY: The pointer came from Box::into_raw when the thread was created, and is only used here.
+  let entry = unsafe { Box::from_raw(entry) };
+  // Threads are switched with IRQs masked, this is the end of the switch.
+  after_switch();
+  cpu::irq_enable();
+  entry();
+  exit();
+}
+
+/// Finishes the current thread.
+fn exit() -> ! {
+  cpu::irq_disable();
+  let joiner = with_scheduler(|scheduler| {
+    let current = scheduler.current;
+    let thread = scheduler.threads.get_mut(&current).expect("Current thread is gone");
+    thread.state = ThreadState::Finished;
+    if thread.detached {
+      scheduler.zombies.push(current);
+    }
+    thread.joiner.take()
+  });
+  if let Some(joiner) = joiner {
+    unpark(joiner);
+  }
+  schedule();
+  unreachable!("Finished thread was switched back to");
+}
+
+fn idle_main() {
+  loop {
+    cpu::wait_for_interrupt();
+    // Whatever the interrupt woke up has been switched to at the end of the IRQ, nothing to do here.
+  }
+}
+
+/// The thread running this code.
+pub fn current() -> ThreadId {
+  with_scheduler(|scheduler| scheduler.current)
+}
+
+/// Lets the other ready threads run before continuing.
+pub fn yield_now() {
+  cpu::without_irqs(schedule);
+}
+
+/// Blocks the current thread for at least `duration`.
+/// Busy-waits like [time::sleep] before [init], or if IRQs are masked and nothing could wake the thread.
+pub fn sleep(duration: Duration) {
+  if !is_initialized() || !cpu::irqs_enabled() {
+    time::sleep(duration);
+    return;
+  }
+  let deadline = Instant::now() + duration;
+  cpu::without_irqs(|| {
+    while Instant::now() < deadline {
+      with_scheduler(|scheduler| {
+        let current = scheduler.current;
+        scheduler.threads.get_mut(&current).expect("Current thread is gone").state = ThreadState::Sleeping(deadline);
+      });
+      timers::at(deadline, wake_sleepers);
+      schedule();
+    }
+  });
+}
+
+/// Blocks the current thread until [unpark] is called for it. Returns immediately if it was unparked while it wasn't
+/// parked. Like with `std::thread::park`, it may also return spuriously, so callers should check their condition again.
+pub fn park() {
+  cpu::without_irqs(|| {
+    let parked = with_scheduler(|scheduler| {
+      let current = scheduler.current;
+      let thread = scheduler.threads.get_mut(&current).expect("Current thread is gone");
+      if core::mem::take(&mut thread.unparked) {
+        return false;
+      }
+      thread.state = ThreadState::Parked;
+      true
+    });
+    if parked {
+      schedule();
+    }
+  });
+}
+
+/// Makes a thread blocked in [park] ready to run, or the next [park] of the thread return immediately.
+/// Can be called from IRQ handlers, the woken thread runs when the IRQ is done.
+pub fn unpark(id: ThreadId) {
+  with_scheduler(|scheduler| {
+    let Some(thread) = scheduler.threads.get_mut(&id) else {
+      return;
+    };
+    match thread.state {
+      ThreadState::Parked => {
+        thread.state = ThreadState::Ready;
+        scheduler.ready.push_back(id);
+        NEED_RESCHEDULE.store(true, Ordering::Relaxed);
+      }
+      ThreadState::Finished => {}
+      _ => thread.unparked = true,
+    }
+  });
+}
+
+/// Timer callback waking every thread whose sleep has ended.
+fn wake_sleepers(_: timers::TimerId) {
+  let now = Instant::now();
+  with_scheduler(|scheduler| {
+    for (&id, thread) in scheduler.threads.iter_mut() {
+      if let ThreadState::Sleeping(deadline) = thread.state
+        && deadline <= now
+      {
+        thread.state = ThreadState::Ready;
+        scheduler.ready.push_back(id);
+        NEED_RESCHEDULE.store(true, Ordering::Relaxed);
+      }
+    }
+  });
+}
+
+fn handle_tick() {
+  arm_timer::arm_timer_clear_interrupt();
+  NEED_RESCHEDULE.store(true, Ordering::Relaxed);
+}
+
+fn handle_irq(context: &mut ExceptionContext) {
+  interrupt::handle_irq(context);
+  // Switching here leaves the IRQ's frame on this thread's stack, it returns from the IRQ when it's switched back to.
+  if NEED_RESCHEDULE.swap(false, Ordering::Relaxed) {
+    schedule();
+  }
+}
+
+/// Switches to the next ready thread, if there is one or the current thread can't continue.
+/// Must be called with IRQs masked, they stay masked until the thread is switched back to.
+fn schedule() {
+  let switch = with_scheduler(|scheduler| {
+    let current = scheduler.current;
+    let thread = scheduler.threads.get_mut(&current).expect("Current thread is gone");
+    thread.check_stack(current);
+    let runnable = thread.state == ThreadState::Running;
+    let next = match scheduler.ready.pop_front() {
+      Some(next) => next,
+      None if runnable => return None,
+      None => scheduler.idle,
+    };
+    if runnable {
+      thread.state = ThreadState::Ready;
+      if current != scheduler.idle {
+        scheduler.ready.push_back(current);
+      }
+    }
+    let save_sp = &raw mut thread.sp;
+
+    let thread = scheduler.threads.get_mut(&next).expect("Ready thread is gone");
+    thread.state = ThreadState::Running;
+    thread.switches = thread.switches.wrapping_add(1);
+    scheduler.current = next;
+    Some((save_sp, thread.sp))
+  });
+
+  if let Some((save_sp, sp)) = switch {
+    // SAFETY: IRQs are masked and the scheduler isn't touched until the switch is done, so `save_sp` still points to
+    // the current thread's entry. `sp` was saved by thread_switch, or is a new thread's SwitchFrame.
+    unsafe { thread_switch(save_sp, sp) };
+    after_switch();
+  }
+}
+
+/// Frees the stacks of finished threads nobody will join, now that none of them is running.
+fn after_switch() {
+  with_scheduler(|scheduler| {
+    for id in core::mem::take(&mut scheduler.zombies) {
+      scheduler.threads.remove(&id);
+    }
+  });
+}
+
+/// What the `threads` shell command shows about a thread.
+#[derive(Clone, Debug)]
+pub struct ThreadInfo {
+  pub id: ThreadId,
+  pub name: &'static str,
+  pub state: ThreadState,
+  /// 0 for `main`, which runs on the boot stack
+  pub stack_size: usize,
+  pub switches: u32,
+}
+
+/// Every thread that hasn't been joined yet, by id.
+pub fn threads() -> Vec<ThreadInfo> {
+  with_scheduler(|scheduler| {
+    scheduler
+      .threads
+      .iter()
+      .map(|(&id, thread)| ThreadInfo {
+        id,
+        name: thread.name,
+        state: thread.state,
+        stack_size: thread.stack_size(),
+        switches: thread.switches,
+      })
+      .collect()
+  })
+}
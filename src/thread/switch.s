@@ -0,0 +1,39 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+// This file is included in thread/mod.rs via global_asm!
+
+.section ".text"
+.fpu vfpv2
+
+// Saves the state of the current thread on its stack, and resumes another one.
+// The frame must match SwitchFrame in thread/mod.rs.
+//
+// All of d0-d15 are saved, not only the callee-saved d8-d15, since a thread preempted
+// in an IRQ may have been using any of them. Must be called with IRQs masked.
+//
+// r0 -> where to save the stack pointer of the current thread
+// r1 -> stack pointer of the thread to resume, as saved by this function
+.globl thread_switch
+thread_switch:
+  // r12 is only pushed to keep the stack 8 byte aligned.
+  push {r4-r12, lr}
+  vpush {d0-d15}
+  vmrs r2, fpscr
+  push {r2, r3}
+  str sp, [r0]
+
+  mov sp, r1
+  pop {r2, r3}
+  vmsr fpscr, r2
+  vpop {d0-d15}
+  pop {r4-r12, pc}
+
+// Where thread_switch "returns" to the first time a thread runs, see SwitchFrame::new.
+// r4 -> argument for thread_start
+.globl thread_trampoline
+thread_trampoline:
+  mov r0, r4
+  // Null frame pointer, to terminate backtraces.
+  mov r11, #0
+  bl thread_start
+  // thread_start never returns.
+  udf #0
@@ -0,0 +1,112 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Arithmetic on system timer counter values, kept free of hardware access so it can be tested on the host
+//! (see `host-tests`).
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::time::Duration;
+
+/// Combines the halves of the 64 bit counter, read in the order of the arguments: high, low, high, low.
+///
+/// If the high half changed, the low half wrapped around the time it was first read, and only the second reads
+/// belong together. It can't wrap again in the few cycles between them.
+pub const fn combine_counter(high: u32, low: u32, high_again: u32, low_again: u32) -> u64 {
+  if high == high_again {
+    ((high as u64) << 32) | low as u64
+  } else {
+    ((high_again as u64) << 32) | low_again as u64
+  }
+}
+
+/// Microseconds in `duration`, rounding a partial microsecond up so waits are never short.
+pub const fn duration_micros_ceil(duration: Duration) -> u128 {
+  duration.as_nanos().div_ceil(1000)
+}
+
+/// Whether the 32 bit counter value `now` has reached `deadline`, allowing for the counter wrapping in between.
+///
+/// Deadlines are taken to be at most 2^31 microseconds (about 35 minutes) ahead of `now`,
+/// anything further ahead looks like it's in the past.
+pub const fn is_reached(now: u32, deadline: u32) -> bool {
+  (now.wrapping_sub(deadline) as i32) >= 0
+}
+
+/// Microseconds from the 32 bit counter value `earlier` to `later`, allowing for the counter wrapping once in between.
+pub const fn micros_between(earlier: u32, later: u32) -> u32 {
+  later.wrapping_sub(earlier)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn combine_counter_without_wrap() {
+    assert_eq!(combine_counter(1, 5, 1, 6), 0x1_0000_0005);
+    assert_eq!(combine_counter(0, 0, 0, 0), 0);
+    assert_eq!(combine_counter(0xFFFF_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF), u64::MAX);
+  }
+
+  #[test]
+  fn combine_counter_low_half_wrapped_between_reads() {
+    // The first low half belongs to the old high half, pairing it with the new one would jump ahead 2^32.
+    assert_eq!(combine_counter(1, 0xFFFF_FFFF, 2, 3), 0x2_0000_0003);
+  }
+
+  #[test]
+  fn combine_counter_torn_high_half() {
+    // The low half read after the wrap must not be paired with the high half read before it.
+    assert_eq!(combine_counter(1, 0, 2, 1), 0x2_0000_0001);
+    assert_ne!(combine_counter(1, 0, 2, 1), 0x1_0000_0000);
+  }
+
+  #[test]
+  fn combine_counter_is_monotonic_across_wrap() {
+    let before = combine_counter(7, 0xFFFF_FFF0, 7, 0xFFFF_FFF8);
+    let during = combine_counter(7, 0xFFFF_FFFE, 8, 2);
+    let after = combine_counter(8, 10, 8, 11);
+    assert!(before < during && during < after);
+  }
+
+  #[test]
+  fn is_reached_without_wrap() {
+    assert!(is_reached(100, 100));
+    assert!(is_reached(101, 100));
+    assert!(!is_reached(99, 100));
+  }
+
+  #[test]
+  fn is_reached_across_wrap() {
+    assert!(is_reached(5, 0xFFFF_FFF0));
+    assert!(!is_reached(0xFFFF_FFF0, 5));
+    assert!(is_reached(0, u32::MAX));
+    assert!(!is_reached(u32::MAX, 0));
+  }
+
+  #[test]
+  fn is_reached_horizon() {
+    // Deadlines up to and including 2^31 ahead are in the future, 2^31 + 1 and beyond look like the past.
+    assert!(!is_reached(0, 0x7FFF_FFFF));
+    assert!(!is_reached(0, 0x8000_0000));
+    assert!(is_reached(0, 0x8000_0001));
+    assert!(!is_reached(0xF000_0000, 0xF000_0000u32.wrapping_add(0x7FFF_FFFF)));
+  }
+
+  #[test]
+  fn micros_between_across_wrap() {
+    assert_eq!(micros_between(10, 25), 15);
+    assert_eq!(micros_between(0xFFFF_FFFE, 3), 5);
+    assert_eq!(micros_between(5, 5), 0);
+    assert_eq!(micros_between(1, 0), u32::MAX);
+  }
+
+  #[test]
+  fn duration_micros_ceil_rounds_up() {
+    assert_eq!(duration_micros_ceil(Duration::ZERO), 0);
+    assert_eq!(duration_micros_ceil(Duration::from_nanos(1)), 1);
+    assert_eq!(duration_micros_ceil(Duration::from_nanos(999)), 1);
+    assert_eq!(duration_micros_ceil(Duration::from_nanos(1000)), 1);
+    assert_eq!(duration_micros_ceil(Duration::from_nanos(1001)), 2);
+    assert_eq!(duration_micros_ceil(Duration::from_micros(7)), 7);
+    assert_eq!(duration_micros_ceil(Duration::from_secs(u64::MAX)), u64::MAX as u128 * 1_000_000);
+  }
+}
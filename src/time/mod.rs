@@ -0,0 +1,139 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Monotonic time since boot, and waiting for it to pass.
+//!
+//! Time comes from the system timer's free running 64 bit microsecond counter, which starts at 0 when the board
+//! powers on and won't wrap for half a million years. The compare registers only see its lower 32 bits though,
+//! which wrap every 71 minutes, see [is_reached] for comparing those.
+//!
+//! To run something later without waiting for it, see [timers].
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::hint::spin_loop;
+use core::ops::{Add, AddAssign, Sub};
+
+pub use core::time::Duration;
+
+use crate::peripheral::drivers::timer::timer_counter;
+
+pub(crate) mod counter;
+pub mod timers;
+
+pub use self::counter::{is_reached, micros_between};
+use self::counter::duration_micros_ceil;
+
+/// A point in time, in microseconds since the board powered on.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Instant(u64);
+
+impl Instant {
+  pub fn now() -> Self {
+    Self(timer_counter())
+  }
+
+  pub const fn from_micros(micros: u64) -> Self {
+    Self(micros)
+  }
+
+  pub const fn as_micros(&self) -> u64 {
+    self.0
+  }
+
+  /// Time from `earlier` to this instant, zero if `earlier` is later.
+  pub fn duration_since(&self, earlier: Instant) -> Duration {
+    Duration::from_micros(self.0.saturating_sub(earlier.0))
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    Instant::now().duration_since(*self)
+  }
+
+  pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+    let micros = u64::try_from(duration_micros_ceil(duration)).ok()?;
+    self.0.checked_add(micros).map(Instant)
+  }
+}
+
+impl Add<Duration> for Instant {
+  type Output = Instant;
+
+  /// Saturates instead of overflowing, an instant that far away never comes anyway.
+  fn add(self, duration: Duration) -> Instant {
+    self.checked_add(duration).unwrap_or(Instant(u64::MAX))
+  }
+}
+
+impl AddAssign<Duration> for Instant {
+  fn add_assign(&mut self, duration: Duration) {
+    *self = *self + duration;
+  }
+}
+
+impl Sub<Instant> for Instant {
+  type Output = Duration;
+
+  fn sub(self, earlier: Instant) -> Duration {
+    self.duration_since(earlier)
+  }
+}
+
+/// A point in time to wait for, or to give up at.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+  /// The deadline `duration` from now.
+  pub fn after(duration: Duration) -> Self {
+    Self(Instant::now() + duration)
+  }
+
+  pub const fn at(instant: Instant) -> Self {
+    Self(instant)
+  }
+
+  pub fn instant(&self) -> Instant {
+    self.0
+  }
+
+  pub fn has_passed(&self) -> bool {
+    Instant::now() >= self.0
+  }
+
+  /// Time left until the deadline, zero once it has passed.
+  pub fn remaining(&self) -> Duration {
+    self.0.duration_since(Instant::now())
+  }
+
+  /// Busy-waits until the deadline has passed.
+  pub fn wait(&self) {
+    while !self.has_passed() {
+      spin_loop();
+    }
+  }
+}
+
+/// Busy-waits for at least `duration`, rounded up to whole microseconds.
+pub fn sleep(duration: Duration) {
+  Deadline::after(duration).wait();
+}
+
+pub fn delay_us(micros: u32) {
+  sleep(Duration::from_micros(micros as u64));
+}
+
+pub fn delay_ms(millis: u32) {
+  sleep(Duration::from_millis(millis as u64));
+}
+
+/// Polls `condition` until it's true or `timeout` has passed. Returns whether the condition became true.
+pub fn wait_for(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+  let deadline = Deadline::after(timeout);
+  loop {
+    if condition() {
+      return true;
+    }
+    if deadline.has_passed() {
+      return false;
+    }
+    spin_loop();
+  }
+}
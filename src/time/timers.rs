@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Software timers, calling a function once or periodically from the system timer interrupt.
+//!
+//! Any number of timers share compare channel 1, which is always set to the nearest deadline.
+//!
+//! ```ignore
+//! let id = timers::after(Duration::from_millis(500), |_| warn!("No reply in time"));
+//! // ... the reply came
+//! timers::cancel(id);
+//! ```
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use rust_alloc::collections::BTreeMap;
+
+use super::{Duration, Instant};
+use crate::peripheral::drivers::interrupt::{self, constants::IrqSource};
+use crate::peripheral::drivers::timer::{Timer, TIMER1};
+use crate::sync::SpinLock;
+
+/// The compare channel the timers run on. Channels 0 and 2 are used by the GPU, 3 is left free.
+const CHANNEL: Timer = TIMER1;
+const CHANNEL_IRQ: IrqSource = IrqSource::SystemTimer1;
+
+/// How far ahead of the counter the compare register is set at least.
+/// Setting it to a value the counter has already passed would only match after the counter wraps, 71 minutes later.
+const MIN_LEAD_MICROS: u32 = 10;
+
+/// Identifies a timer, for cancelling it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TimerId(u32);
+
+/// Called when a timer expires, in the IRQ handler with IRQs masked, so it should be quick.
+pub type TimerCallback = fn(TimerId);
+
+#[derive(Clone, Copy)]
+struct Entry {
+  callback: TimerCallback,
+  /// Set for periodic timers
+  period: Option<Duration>,
+}
+
+/// Pending timers, ordered by deadline. The id breaks ties, so timers with the same deadline run in the order they were added.
+static TIMERS: SpinLock<BTreeMap<(Instant, TimerId), Entry>> = SpinLock::new(BTreeMap::new());
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Hooks the timers up to the system timer interrupt.
+/// Requires the interrupt controller to be initialized, see [interrupt::init].
+pub fn init() {
+  CHANNEL.clear_interrupt();
+  interrupt::register_handler(CHANNEL_IRQ, handle_interrupt);
+  interrupt::enable(CHANNEL_IRQ);
+}
+
+/// Calls `callback` once, `delay` from now.
+pub fn after(delay: Duration, callback: TimerCallback) -> TimerId {
+  add(Instant::now() + delay, Entry { callback, period: None })
+}
+
+/// Calls `callback` at `deadline`, or as soon as possible if it has passed.
+pub fn at(deadline: Instant, callback: TimerCallback) -> TimerId {
+  add(deadline, Entry { callback, period: None })
+}
+
+/// Calls `callback` every `period`, starting one period from now, until the timer is cancelled.
+///
+/// Ticks are kept on the schedule they started on. If the callbacks fall more than a period behind, the missed ticks are skipped.
+pub fn every(period: Duration, callback: TimerCallback) -> TimerId {
+  // A zero period would have the interrupt handler run the timer forever.
+  let period = period.max(Duration::from_micros(1));
+  add(Instant::now() + period, Entry { callback, period: Some(period) })
+}
+
+/// Stops a timer. Returns whether it was still pending, a one-shot timer that has already run isn't.
+pub fn cancel(id: TimerId) -> bool {
+  TIMERS.with(|timers| {
+    let key = timers.keys().find(|(_, timer)| *timer == id).copied();
+    key.is_some_and(|key| timers.remove(&key).is_some())
+  })
+}
+
+/// Number of pending timers.
+pub fn pending() -> usize {
+  TIMERS.with(|timers| timers.len())
+}
+
+fn add(deadline: Instant, entry: Entry) -> TimerId {
+  let id = TimerId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+  TIMERS.with(|timers| {
+    timers.insert((deadline, id), entry);
+    reprogram(timers);
+  });
+  id
+}
+
+/// Sets the compare register to the nearest deadline.
+fn reprogram(timers: &BTreeMap<(Instant, TimerId), Entry>) {
+  let Some(&(deadline, _)) = timers.keys().next() else {
+    // A stale compare value only causes an interrupt that finds nothing to run.
+    return;
+  };
+  let now = Instant::now();
+  let earliest = now + Duration::from_micros(MIN_LEAD_MICROS as u64);
+  // Only the lower 32 bits are compared. A deadline further than that away matches early, and is reprogrammed then.
+  CHANNEL.set_compare(deadline.max(earliest).as_micros() as u32);
+}
+
+fn handle_interrupt() {
+  CHANNEL.clear_interrupt();
+
+  loop {
+    let now = Instant::now();
+    let expired = TIMERS.with(|timers| {
+      let entry = timers.first_entry().filter(|entry| entry.key().0 <= now)?;
+      let ((deadline, id), timer) = entry.remove_entry();
+      if let Some(period) = timer.period {
+        let next = deadline + period;
+        timers.insert((if next > now { next } else { now + period }, id), timer);
+      }
+      Some((id, timer.callback))
+    });
+    match expired {
+      Some((id, callback)) => callback(id),
+      None => break,
+    }
+  }
+
+  TIMERS.with(|timers| reprogram(timers));
+}
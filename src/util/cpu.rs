@@ -0,0 +1,224 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "These are utility functions, they may or may not be used")]
+
+use core::arch::asm;
+
+/// I (IRQ mask) bit in the CPSR
+const CPSR_IRQ_MASK: u32 = 1 << 7;
+/// F (FIQ mask) bit in the CPSR
+const CPSR_FIQ_MASK: u32 = 1 << 6;
+
+/// Mode field in the CPSR
+const CPSR_MODE_MASK: u32 = 0x1F;
+
+/// Saved IRQ mask state, returned by [irq_save] and consumed by [irq_restore].
+#[repr(transparent)]
+#[derive(Copy, Clone)]
+pub struct IrqState(u32);
+
+impl IrqState {
+  /// Whether IRQs were enabled when this state was saved.
+  #[inline(always)]
+  pub fn irqs_enabled(&self) -> bool {
+    self.0 & CPSR_IRQ_MASK == 0
+  }
+
+  /// Whether FIQs were enabled when this state was saved.
+  #[inline(always)]
+  pub fn fiqs_enabled(&self) -> bool {
+    self.0 & CPSR_FIQ_MASK == 0
+  }
+}
+
+#[inline(always)]
+pub fn read_cpsr() -> u32 {
+  let cpsr: u32;
+  // SAFETY: Reading the CPSR has no side effects.
+  unsafe { asm!("mrs {}, cpsr", out(reg) cpsr, options(nomem, nostack, preserves_flags)) };
+  cpsr
+}
+
+/// Name of the processor mode encoded in the mode field of a CPSR value.
+pub fn mode_name(cpsr: u32) -> &'static str {
+  match cpsr & CPSR_MODE_MASK {
+    0x10 => "usr",
+    0x11 => "fiq",
+    0x12 => "irq",
+    0x13 => "svc",
+    0x17 => "abt",
+    0x1B => "und",
+    0x1F => "sys",
+    _ => "???",
+  }
+}
+
+/// Unmask IRQs on the current core.
+#[inline(always)]
+pub fn irq_enable() {
+  // No `nomem` here, memory accesses must not be reordered across this point.
+  // SAFETY: Changing the IRQ mask does not violate memory safety by itself.
+  unsafe { asm!("cpsie i", options(nostack, preserves_flags)) };
+}
+
+/// Mask IRQs on the current core.
+#[inline(always)]
+pub fn irq_disable() {
+  // SAFETY: See [irq_enable].
+  unsafe { asm!("cpsid i", options(nostack, preserves_flags)) };
+}
+
+/// Unmask FIQs on the current core.
+#[inline(always)]
+pub fn fiq_enable() {
+  // SAFETY: See [irq_enable].
+  unsafe { asm!("cpsie f", options(nostack, preserves_flags)) };
+}
+
+/// Mask FIQs on the current core.
+#[inline(always)]
+pub fn fiq_disable() {
+  // SAFETY: See [irq_enable].
+  unsafe { asm!("cpsid f", options(nostack, preserves_flags)) };
+}
+
+#[inline(always)]
+pub fn irqs_enabled() -> bool {
+  read_cpsr() & CPSR_IRQ_MASK == 0
+}
+
+/// Mask IRQs and return the previous mask state.
+#[inline(always)]
+pub fn irq_save() -> IrqState {
+  let state = IrqState(read_cpsr());
+  irq_disable();
+  state
+}
+
+/// Restore the IRQ mask state saved by [irq_save].
+#[inline(always)]
+pub fn irq_restore(state: IrqState) {
+  if state.irqs_enabled() {
+    irq_enable();
+  }
+}
+
+/// Run `f` with IRQs masked, restoring the previous mask state afterwards.
+#[inline(always)]
+pub fn without_irqs<R>(f: impl FnOnce() -> R) -> R {
+  let state = irq_save();
+  let result = f();
+  irq_restore(state);
+  result
+}
+
+/// Mask both IRQs and FIQs and return the previous mask state.
+#[inline(always)]
+pub fn interrupts_save() -> IrqState {
+  let state = IrqState(read_cpsr());
+  // SAFETY: See [irq_enable].
+  unsafe { asm!("cpsid if", options(nostack, preserves_flags)) };
+  state
+}
+
+/// Restore the IRQ and FIQ mask state saved by [interrupts_save].
+#[inline(always)]
+pub fn interrupts_restore(state: IrqState) {
+  if state.fiqs_enabled() {
+    fiq_enable();
+  }
+  if state.irqs_enabled() {
+    irq_enable();
+  }
+}
+
+/// Run `f` with both IRQs and FIQs masked, restoring the previous mask state afterwards.
+#[inline(always)]
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+  let state = interrupts_save();
+  let result = f();
+  interrupts_restore(state);
+  result
+}
+
+/// Put the core to sleep until an interrupt is pending.
+/// Note that this returns even if the pending interrupt is masked.
+#[inline(always)]
+pub fn wait_for_interrupt() {
+  // The CP15 "Wait For Interrupt" operation is used, as it's the documented way for the ARM1176.
+  // SAFETY: Halting until an interrupt arrives has no memory safety implications.
+  unsafe { asm!("mcr p15, 0, {}, c7, c0, 4", in(reg) 0u32, options(nomem, nostack, preserves_flags)) };
+}
+
+/// Enables the VFP coprocessor, so floating point instructions and the VFP registers can be used.
+pub fn vfp_enable() {
+  // SAFETY: Only grants access to the VFP and turns it on, which doesn't change any state the kernel relies on.
+  unsafe {
+    // Full access to coprocessors 10 and 11 (the VFP) in the Coprocessor Access Control Register.
+    asm!(
+      "mrc p15, 0, {value}, c1, c0, 2",
+      "orr {value}, {value}, #(0xF << 20)",
+      "mcr p15, 0, {value}, c1, c0, 2",
+      // Flush Prefetch Buffer, the ARMv6 instruction barrier, so the new access rights apply to what follows.
+      "mcr p15, 0, {zero}, c7, c5, 4",
+      value = out(reg) _,
+      zero = in(reg) 0u32,
+      options(nostack, preserves_flags),
+    );
+    // Set the EN bit in FPEXC. This is `vmsr fpexc`, spelled out as the coprocessor write it is.
+    asm!("mcr p10, 7, {}, c8, c0, 0", in(reg) 1u32 << 30, options(nostack, preserves_flags));
+  }
+}
+
+/// Busy-waits for at least `cycles` CPU cycles, for hardware that needs a short setup time counted in cycles.
+#[inline(never)]
+pub fn delay_cycles(cycles: u32) {
+  for _ in 0..cycles {
+    // SAFETY: A no-op, it's only there so the loop isn't optimized away.
+    unsafe { asm!("nop", options(nomem, nostack, preserves_flags)) };
+  }
+}
+
+/// Data Memory Barrier
+///
+/// Required between accesses to different peripherals, as the BCM2835 peripheral bus
+/// does not guarantee ordering between them.
+#[inline(always)]
+pub fn data_memory_barrier() {
+  // ARMv6 has no `dmb` instruction, the CP15 c7 operation is used instead.
+  // SAFETY: A barrier has no memory safety implications.
+  unsafe { asm!("mcr p15, 0, {}, c7, c10, 5", in(reg) 0u32, options(nostack, preserves_flags)) };
+}
+
+/// Data Synchronization Barrier
+#[inline(always)]
+pub fn data_sync_barrier() {
+  // SAFETY: A barrier has no memory safety implications.
+  unsafe { asm!("mcr p15, 0, {}, c7, c10, 4", in(reg) 0u32, options(nostack, preserves_flags)) };
+}
+
+/// Data Fault Address Register, holds the address that caused the last data abort.
+#[inline(always)]
+pub fn data_fault_address() -> u32 {
+  let value: u32;
+  // SAFETY: Reading a CP15 register has no side effects.
+  unsafe { asm!("mrc p15, 0, {}, c6, c0, 0", out(reg) value, options(nomem, nostack, preserves_flags)) };
+  value
+}
+
+/// Data Fault Status Register, describes the cause of the last data abort.
+#[inline(always)]
+pub fn data_fault_status() -> u32 {
+  let value: u32;
+  // SAFETY: Reading a CP15 register has no side effects.
+  unsafe { asm!("mrc p15, 0, {}, c5, c0, 0", out(reg) value, options(nomem, nostack, preserves_flags)) };
+  value
+}
+
+/// Instruction Fault Status Register, describes the cause of the last prefetch abort.
+#[inline(always)]
+pub fn instruction_fault_status() -> u32 {
+  let value: u32;
+  // SAFETY: Reading a CP15 register has no side effects.
+  unsafe { asm!("mrc p15, 0, {}, c5, c0, 1", out(reg) value, options(nomem, nostack, preserves_flags)) };
+  value
+}
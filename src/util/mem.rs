@@ -48,6 +48,12 @@ impl Register {
     unsafe { Register::new(addr) }
   }
 
+  /// The physical address of the register.
+  #[inline(always)]
+  pub fn address(&self) -> u32 {
+    self.0 as u32
+  }
+
   #[inline(always)]
   pub fn read(&self) -> u32 {
     // SAFETY: Caller has already ensured that the address is valid and aligned when creating the Register.
@@ -73,6 +79,21 @@ impl Register {
   }
 }
 
+/// A [Register] with the name of its constant, so it can be looked up by name.
+#[derive(Copy, Clone)]
+pub struct NamedRegister {
+  pub name: &'static str,
+  pub register: Register,
+}
+
+/// Lists register constants with their names, e.g. `named_registers![UART_DR, UART_FR]`.
+#[macro_export]
+macro_rules! named_registers {
+  ($($register:ident),* $(,)?) => {
+    &[$($crate::util::mem::NamedRegister { name: stringify!($register), register: $register }),*]
+  };
+}
+
 impl Into<*mut u32> for &Register {
   #[inline(always)]
   fn into(self) -> *mut u32 {
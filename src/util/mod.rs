@@ -1,2 +1,4 @@
 // Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
 pub mod mem;
+pub mod cpu;
+pub mod ring_buffer;
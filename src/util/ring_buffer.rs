@@ -0,0 +1,102 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+#![allow(unused, reason = "These are utility types, they may or may not be used")]
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A fixed size single-producer single-consumer ring buffer.
+///
+/// The producer only writes `head`, the consumer only writes `tail`, so one side
+/// may run in an interrupt handler while the other runs in normal code without locking.
+/// The indices run freely and wrap around, `N` must be a power of two.
+pub struct RingBuffer<T: Copy, const N: usize> {
+  buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+  // Index of the next slot to write, only written by the producer.
+  head: AtomicUsize,
+  // Index of the next slot to read, only written by the consumer.
+  tail: AtomicUsize,
+}
+
+// SAFETY: Slots are handed between the producer and the consumer through the atomic indices,
+// the contract of [RingBuffer::push] and [RingBuffer::pop] rules out concurrent access to a slot.
+unsafe impl<T: Copy + Send, const N: usize> Sync for RingBuffer<T, N> {}
+
+impl<T: Copy, const N: usize> RingBuffer<T, N> {
+  const MASK: usize = {
+    assert!(N.is_power_of_two(), "RingBuffer capacity must be a power of two");
+    N - 1
+  };
+
+  pub const fn new() -> Self {
+    Self {
+      buffer: UnsafeCell::new([MaybeUninit::uninit(); N]),
+      head: AtomicUsize::new(0),
+      tail: AtomicUsize::new(0),
+    }
+  }
+
+  #[inline]
+  pub const fn capacity(&self) -> usize {
+    N
+  }
+
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.head.load(Ordering::Acquire).wrapping_sub(self.tail.load(Ordering::Acquire))
+  }
+
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  #[inline]
+  pub fn is_full(&self) -> bool {
+    self.len() >= N
+  }
+
+  /// Appends a value, returning `false` if the buffer is full.
+  ///
+  /// SAFETY: Caller must ensure that there is only a single producer at a time,
+  /// i.e. [RingBuffer::push] is never called concurrently with itself.
+  #[inline]
+  pub unsafe fn push(&self, value: T) -> bool {
+    let head = self.head.load(Ordering::Relaxed);
+    let tail = self.tail.load(Ordering::Acquire);
+    if head.wrapping_sub(tail) >= N {
+      return false;
+    }
+    // SAFETY: The slot at `head` is not visible to the consumer until `head` is published below,
+    // and the caller ensures there's no other producer.
+    unsafe { (*self.buffer.get())[head & Self::MASK].write(value) };
+    self.head.store(head.wrapping_add(1), Ordering::Release);
+    true
+  }
+
+  /// Removes the oldest value, returning `None` if the buffer is empty.
+  ///
+  /// SAFETY: Caller must ensure that there is only a single consumer at a time,
+  /// i.e. [RingBuffer::pop] and [RingBuffer::clear] are never called concurrently with each other.
+  #[inline]
+  pub unsafe fn pop(&self) -> Option<T> {
+    let tail = self.tail.load(Ordering::Relaxed);
+    let head = self.head.load(Ordering::Acquire);
+    if head == tail {
+      return None;
+    }
+    // SAFETY: The producer initialized this slot before publishing `head`,
+    // and won't touch it again until `tail` moves past it.
+    let value = unsafe { (*self.buffer.get())[tail & Self::MASK].assume_init() };
+    self.tail.store(tail.wrapping_add(1), Ordering::Release);
+    Some(value)
+  }
+
+  /// Discards every value currently in the buffer.
+  ///
+  /// SAFETY: Same as [RingBuffer::pop], this is a consumer operation.
+  #[inline]
+  pub unsafe fn clear(&self) {
+    self.tail.store(self.head.load(Ordering::Acquire), Ordering::Release);
+  }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Linear framebuffer, allocated by the VideoCore firmware through the mailbox.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use crate::peripheral::drivers::mailbox::{self, constants::tags, MailboxError, PropertyMessage};
+
+/// Used if the firmware doesn't know the size of the display (e.g. nothing is connected).
+pub const DEFAULT_WIDTH: u32 = 640;
+pub const DEFAULT_HEIGHT: u32 = 480;
+
+/// Bits per pixel, every pixel is a single [u32].
+const DEPTH: u32 = 32;
+/// Values of the pixel order tags.
+const PIXEL_ORDER_BGR: u32 = 0;
+const PIXEL_ORDER_RGB: u32 = 1;
+/// Alignment of the buffer requested from the firmware, in bytes.
+const BUFFER_ALIGNMENT: u32 = 16;
+/// Turns the bus address of the buffer into an ARM physical address.
+const BUS_ADDRESS_MASK: u32 = 0x3FFF_FFFF;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FramebufferError {
+  Mailbox(MailboxError),
+  /// The firmware picked a different depth than the 32 bits per pixel we asked for
+  UnsupportedDepth(u32),
+  /// The firmware didn't hand out a buffer
+  NoBuffer,
+}
+
+impl From<MailboxError> for FramebufferError {
+  fn from(error: MailboxError) -> Self {
+    Self::Mailbox(error)
+  }
+}
+
+/// A 24 bit RGB color, stored as `0x00RRGGBB`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Color(pub u32);
+
+impl Color {
+  pub const BLACK: Self = Self::rgb(0, 0, 0);
+  pub const WHITE: Self = Self::rgb(0xFF, 0xFF, 0xFF);
+
+  pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+    Self((r as u32) << 16 | (g as u32) << 8 | b as u32)
+  }
+}
+
+/// What the firmware set the framebuffer up as.
+#[derive(Clone, Copy, Debug)]
+pub struct FramebufferInfo {
+  pub width: u32,
+  pub height: u32,
+  /// Bytes between the start of two rows
+  pub pitch: u32,
+  /// ARM physical address of the buffer
+  pub address: u32,
+  /// Size of the buffer in bytes
+  pub size: u32,
+}
+
+pub struct Framebuffer {
+  info: FramebufferInfo,
+  /// Pixels are stored as `0x00BBGGRR`, [Color]s need their red and blue swapped.
+  bgr: bool,
+}
+
+impl Framebuffer {
+  /// Asks the firmware for a `width` by `height` framebuffer with 32 bits per pixel.
+  /// A size of zero uses the size of the display, or [DEFAULT_WIDTH] by [DEFAULT_HEIGHT] if it's unknown.
+  pub fn allocate(width: u32, height: u32) -> Result<Self, FramebufferError> {
+    let (width, height) = match (width, height) {
+      (0, _) | (_, 0) => match mailbox::property_call::<2>(tags::GET_PHYSICAL_SIZE, &[]) {
+        Ok([width, height]) if width != 0 && height != 0 => (width, height),
+        _ => (DEFAULT_WIDTH, DEFAULT_HEIGHT),
+      },
+      size => size,
+    };
+
+    let mut message = PropertyMessage::new();
+    let physical_size = message.add_tag(tags::SET_PHYSICAL_SIZE, &[width, height], 2)?;
+    message.add_tag(tags::SET_VIRTUAL_SIZE, &[width, height], 2)?;
+    message.add_tag(tags::SET_VIRTUAL_OFFSET, &[0, 0], 2)?;
+    let depth = message.add_tag(tags::SET_DEPTH, &[DEPTH], 1)?;
+    let pixel_order = message.add_tag(tags::SET_PIXEL_ORDER, &[PIXEL_ORDER_RGB], 1)?;
+    let buffer = message.add_tag(tags::ALLOCATE_BUFFER, &[BUFFER_ALIGNMENT], 2)?;
+    let pitch = message.add_tag(tags::GET_PITCH, &[], 1)?;
+    message.send()?;
+
+    let depth = *message.response(depth)?.first().ok_or(MailboxError::ShortResponse(tags::SET_DEPTH))?;
+    if depth != DEPTH {
+      return Err(FramebufferError::UnsupportedDepth(depth));
+    }
+    let &[width, height, ..] = message.response(physical_size)? else {
+      return Err(MailboxError::ShortResponse(tags::SET_PHYSICAL_SIZE).into());
+    };
+    let &[address, size, ..] = message.response(buffer)? else {
+      return Err(MailboxError::ShortResponse(tags::ALLOCATE_BUFFER).into());
+    };
+    let pitch = *message.response(pitch)?.first().ok_or(MailboxError::ShortResponse(tags::GET_PITCH))?;
+    // Older firmware doesn't answer the pixel order, it defaults to BGR.
+    let bgr = message.response(pixel_order).ok().and_then(|order| order.first().copied()) != Some(PIXEL_ORDER_RGB);
+
+    if address == 0 || size == 0 || pitch < width * 4 || size < pitch * height {
+      return Err(FramebufferError::NoBuffer);
+    }
+
+    Ok(Self {
+      info: FramebufferInfo { width, height, pitch, address: address & BUS_ADDRESS_MASK, size },
+      bgr,
+    })
+  }
+
+  #[inline]
+  pub fn info(&self) -> FramebufferInfo {
+    self.info
+  }
+
+  #[inline]
+  pub fn width(&self) -> usize {
+    self.info.width as usize
+  }
+
+  #[inline]
+  pub fn height(&self) -> usize {
+    self.info.height as usize
+  }
+
+  // Pixels between the start of two rows.
+  #[inline]
+  fn stride(&self) -> usize {
+    self.info.pitch as usize / 4
+  }
+
+  #[inline]
+  fn row(&self, y: usize) -> *mut u32 {
+    (self.info.address as usize + y * self.info.pitch as usize) as *mut u32
+  }
+
+  /// The value stored in the buffer for `color`.
+  #[inline]
+  fn native(&self, color: Color) -> u32 {
+    if self.bgr {
+      (color.0 & 0xFF) << 16 | (color.0 & 0xFF00) | (color.0 >> 16 & 0xFF)
+    } else {
+      color.0
+    }
+  }
+
+  pub fn put_pixel(&mut self, x: usize, y: usize, color: Color) {
+    if x >= self.width() || y >= self.height() {
+      return;
+    }
+    // SAFETY: The pixel is within the buffer the firmware gave us.
+    unsafe { self.row(y).add(x).write_volatile(self.native(color)) };
+  }
+
+  /// Fills a rectangle, clipped to the screen.
+  pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+    let x_end = x.saturating_add(width).min(self.width());
+    let y_end = y.saturating_add(height).min(self.height());
+    let value = self.native(color);
+    for y in y..y_end {
+      let row = self.row(y);
+      for x in x..x_end {
+        // SAFETY: The pixel is within the buffer the firmware gave us.
+        unsafe { row.add(x).write_volatile(value) };
+      }
+    }
+  }
+
+  /// Inverts the colors of a rectangle, clipped to the screen. Doing it twice restores the rectangle.
+  pub fn invert_rect(&mut self, x: usize, y: usize, width: usize, height: usize) {
+    let x_end = x.saturating_add(width).min(self.width());
+    let y_end = y.saturating_add(height).min(self.height());
+    for y in y..y_end {
+      let row = self.row(y);
+      for x in x..x_end {
+        // SAFETY: The pixel is within the buffer the firmware gave us.
+        unsafe {
+          let pixel = row.add(x);
+          pixel.write_volatile(pixel.read_volatile() ^ 0x00FF_FFFF);
+        }
+      }
+    }
+  }
+
+  pub fn clear(&mut self, color: Color) {
+    self.fill_rect(0, 0, self.width(), self.height(), color);
+  }
+
+  /// Moves the whole screen up by `lines` pixel rows, filling the rows freed at the bottom with `color`.
+  pub fn scroll_up(&mut self, lines: usize, color: Color) {
+    let lines = lines.min(self.height());
+    let kept = self.height() - lines;
+    // SAFETY: Both ranges are within the buffer, copy handles the overlap.
+    unsafe { core::ptr::copy(self.row(lines), self.row(0), kept * self.stride()) };
+    self.fill_rect(0, kept, self.width(), lines, color);
+  }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Video output: a framebuffer from the VideoCore firmware, and a text console drawn on it.
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use crate::console;
+use crate::sync::Mutex;
+use crate::util::cpu;
+
+use self::framebuffer::{Framebuffer, FramebufferError, FramebufferInfo};
+use self::terminal::Terminal;
+
+pub mod font;
+pub mod framebuffer;
+pub mod terminal;
+
+// Threads wait for their turn to draw, interrupts stay enabled since drawing and scrolling the whole framebuffer takes
+// milliseconds.
+static TERMINAL: Mutex<Option<Terminal>> = Mutex::new(None);
+
+/// Allocates a framebuffer the size of the display and adds a terminal on it to the console outputs.
+/// Returns the framebuffer and the terminal size in columns and rows.
+pub fn init_console() -> Result<(FramebufferInfo, usize, usize), FramebufferError> {
+  let terminal = Terminal::new(Framebuffer::allocate(0, 0)?);
+  let result = (terminal.framebuffer().info(), terminal.columns(), terminal.rows());
+
+  *TERMINAL.lock() = Some(terminal);
+  console::add_sink(write_terminal);
+  Ok(result)
+}
+
+fn write_terminal(bytes: &[u8]) {
+  // With IRQs masked (in an IRQ handler, under a SpinLock or in a panic) this can't wait for the lock. Output that
+  // arrives while the terminal is drawing is dropped then, the other console outputs still get it.
+  let mut terminal = if cpu::irqs_enabled() { Some(TERMINAL.lock()) } else { TERMINAL.try_lock() };
+  if let Some(terminal) = terminal.as_deref_mut().and_then(Option::as_mut) {
+    terminal.write(bytes);
+  }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2025 Kārlis Čerņavskis, licensed under GNU AGPL v3.0
+//! Text terminal drawn on a [Framebuffer].
+//!
+//! Understands the control characters the shell uses (`\n`, `\r`, backspace and tab)
+//! and a subset of ANSI escape sequences:
+//! - `ESC[<n>m`: colors 30-37, 40-47, 90-97 and 100-107, defaults 39 and 49, bold 1 and reset 0
+//! - `ESC[<n>J`: clear the screen, `ESC[<n>K`: clear the line
+//! - `ESC[<row>;<column>H`: move the cursor, `ESC[<n>A`, `B`, `C`, `D`: move it up, down, right and left
+#![allow(unused, reason = "This module may be unused, as it is providing functionality that may not be used anywhere")]
+
+use super::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use super::framebuffer::{Color, Framebuffer};
+
+/// The 16 ANSI colors, normal ones followed by bright ones.
+const PALETTE: [Color; 16] = [
+  Color::rgb(0x00, 0x00, 0x00),
+  Color::rgb(0xAA, 0x00, 0x00),
+  Color::rgb(0x00, 0xAA, 0x00),
+  Color::rgb(0xAA, 0x55, 0x00),
+  Color::rgb(0x00, 0x00, 0xAA),
+  Color::rgb(0xAA, 0x00, 0xAA),
+  Color::rgb(0x00, 0xAA, 0xAA),
+  Color::rgb(0xAA, 0xAA, 0xAA),
+  Color::rgb(0x55, 0x55, 0x55),
+  Color::rgb(0xFF, 0x55, 0x55),
+  Color::rgb(0x55, 0xFF, 0x55),
+  Color::rgb(0xFF, 0xFF, 0x55),
+  Color::rgb(0x55, 0x55, 0xFF),
+  Color::rgb(0xFF, 0x55, 0xFF),
+  Color::rgb(0x55, 0xFF, 0xFF),
+  Color::rgb(0xFF, 0xFF, 0xFF),
+];
+
+const DEFAULT_FOREGROUND: u8 = 7;
+const DEFAULT_BACKGROUND: u8 = 0;
+/// Offset from a normal color to its bright version in [PALETTE].
+const BRIGHT: u8 = 8;
+
+/// Glyphs are scaled up until there's no more room for this many columns.
+const MIN_COLUMNS: usize = 80;
+const TAB_WIDTH: usize = 8;
+/// Pixel rows of the underline cursor, before scaling.
+const CURSOR_HEIGHT: usize = 1;
+
+const ESC: u8 = 0x1B;
+const MAX_PARAMS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+  Normal,
+  /// Got ESC
+  Escape,
+  /// Got ESC [, collecting parameters
+  Csi,
+}
+
+pub struct Terminal {
+  framebuffer: Framebuffer,
+  /// Glyphs are drawn `scale` times their size
+  scale: usize,
+  columns: usize,
+  rows: usize,
+  column: usize,
+  row: usize,
+  /// Indices into [PALETTE]
+  foreground: u8,
+  background: u8,
+  bold: bool,
+  state: EscapeState,
+  params: [u16; MAX_PARAMS],
+  param_count: usize,
+}
+
+impl Terminal {
+  /// Takes over `framebuffer` and clears it.
+  pub fn new(framebuffer: Framebuffer) -> Self {
+    let scale = (framebuffer.width() / (MIN_COLUMNS * GLYPH_WIDTH)).max(1);
+    // Tiny screens still get a cell, it's just clipped.
+    let columns = (framebuffer.width() / (GLYPH_WIDTH * scale)).max(1);
+    let rows = (framebuffer.height() / (GLYPH_HEIGHT * scale)).max(1);
+    let mut terminal = Self {
+      framebuffer,
+      scale,
+      columns,
+      rows,
+      column: 0,
+      row: 0,
+      foreground: DEFAULT_FOREGROUND,
+      background: DEFAULT_BACKGROUND,
+      bold: false,
+      state: EscapeState::Normal,
+      params: [0; MAX_PARAMS],
+      param_count: 0,
+    };
+    terminal.clear();
+    terminal.toggle_cursor();
+    terminal
+  }
+
+  #[inline]
+  pub fn columns(&self) -> usize {
+    self.columns
+  }
+
+  #[inline]
+  pub fn rows(&self) -> usize {
+    self.rows
+  }
+
+  #[inline]
+  pub fn framebuffer(&self) -> &Framebuffer {
+    &self.framebuffer
+  }
+
+  pub fn write(&mut self, bytes: &[u8]) {
+    self.toggle_cursor();
+    for &b in bytes {
+      self.write_byte(b);
+    }
+    self.toggle_cursor();
+  }
+
+  /// Clears the screen and moves the cursor to the top left.
+  pub fn clear(&mut self) {
+    let background = self.background_color();
+    self.framebuffer.clear(background);
+    self.column = 0;
+    self.row = 0;
+  }
+
+  fn cell_width(&self) -> usize {
+    GLYPH_WIDTH * self.scale
+  }
+
+  fn cell_height(&self) -> usize {
+    GLYPH_HEIGHT * self.scale
+  }
+
+  fn foreground_color(&self) -> Color {
+    let index = if self.bold && self.foreground < BRIGHT { self.foreground + BRIGHT } else { self.foreground };
+    PALETTE[index as usize]
+  }
+
+  fn background_color(&self) -> Color {
+    PALETTE[self.background as usize]
+  }
+
+  // Shows or hides the cursor, an underline in the cell the next character goes to.
+  fn toggle_cursor(&mut self) {
+    if self.column >= self.columns || self.row >= self.rows {
+      return;
+    }
+    let height = CURSOR_HEIGHT * self.scale;
+    let x = self.column * self.cell_width();
+    let y = (self.row + 1) * self.cell_height() - height;
+    let width = self.cell_width();
+    self.framebuffer.invert_rect(x, y, width, height);
+  }
+
+  fn write_byte(&mut self, b: u8) {
+    match self.state {
+      EscapeState::Normal => self.write_normal(b),
+      EscapeState::Escape => {
+        self.state = match b {
+          b'[' => {
+            self.params = [0; MAX_PARAMS];
+            self.param_count = 0;
+            EscapeState::Csi
+          }
+          _ => EscapeState::Normal,
+        };
+      }
+      EscapeState::Csi => match b {
+        b'0'..=b'9' => {
+          self.param_count = self.param_count.max(1);
+          let param = &mut self.params[self.param_count - 1];
+          *param = param.saturating_mul(10).saturating_add((b - b'0') as u16);
+        }
+        b';' => self.param_count = (self.param_count.max(1) + 1).min(MAX_PARAMS),
+        0x40..=0x7E => {
+          self.state = EscapeState::Normal;
+          self.execute(b);
+        }
+        _ => self.state = EscapeState::Normal,
+      },
+    }
+  }
+
+  fn write_normal(&mut self, b: u8) {
+    match b {
+      ESC => self.state = EscapeState::Escape,
+      b'\n' => self.new_line(),
+      b'\r' => self.column = 0,
+      0x08 => self.column = self.column.min(self.columns - 1).saturating_sub(1),
+      b'\t' => {
+        let next = (self.column / TAB_WIDTH + 1) * TAB_WIDTH;
+        self.column = next.min(self.columns - 1);
+      }
+      // Skip the continuation bytes of UTF-8 sequences, the first byte already got a replacement glyph.
+      0x80..=0xBF => {}
+      b if b < b' ' || b == 0x7F => {}
+      b => self.put_char(b),
+    }
+  }
+
+  fn put_char(&mut self, c: u8) {
+    // The previous character filled the line.
+    if self.column >= self.columns {
+      self.new_line();
+    }
+    let glyph = font::glyph(c);
+    let foreground = self.foreground_color();
+    let background = self.background_color();
+    let (cell_x, cell_y) = (self.column * self.cell_width(), self.row * self.cell_height());
+    for y in 0..GLYPH_HEIGHT {
+      for x in 0..GLYPH_WIDTH {
+        let color = if font::pixel(glyph, x, y) { foreground } else { background };
+        self.framebuffer.fill_rect(cell_x + x * self.scale, cell_y + y * self.scale, self.scale, self.scale, color);
+      }
+    }
+    self.column += 1;
+  }
+
+  fn new_line(&mut self) {
+    self.column = 0;
+    if self.row + 1 < self.rows {
+      self.row += 1;
+    } else {
+      let background = self.background_color();
+      let lines = self.cell_height();
+      self.framebuffer.scroll_up(lines, background);
+    }
+  }
+
+  // Parameter `index` of the current escape sequence, `default` if it's missing or zero.
+  fn param(&self, index: usize, default: u16) -> u16 {
+    match self.params[index] {
+      0 => default,
+      value => value,
+    }
+  }
+
+  fn execute(&mut self, command: u8) {
+    let count = self.param(0, 1) as usize;
+    match command {
+      b'm' => self.select_graphic_rendition(),
+      b'A' => self.row = self.row.saturating_sub(count),
+      b'B' => self.row = (self.row + count).min(self.rows - 1),
+      b'C' => self.column = (self.column + count).min(self.columns - 1),
+      b'D' => self.column = self.column.min(self.columns - 1).saturating_sub(count),
+      b'H' | b'f' => {
+        self.row = (self.param(0, 1) as usize).min(self.rows) - 1;
+        self.column = (self.param(1, 1) as usize).min(self.columns) - 1;
+      }
+      b'J' => match self.params[0] {
+        0 => {
+          self.clear_line_range(self.column, self.columns);
+          self.clear_rows(self.row + 1, self.rows);
+        }
+        1 => {
+          self.clear_rows(0, self.row);
+          self.clear_line_range(0, self.column + 1);
+        }
+        _ => self.clear_rows(0, self.rows),
+      },
+      b'K' => match self.params[0] {
+        0 => self.clear_line_range(self.column, self.columns),
+        1 => self.clear_line_range(0, self.column + 1),
+        _ => self.clear_line_range(0, self.columns),
+      },
+      _ => {}
+    }
+  }
+
+  fn select_graphic_rendition(&mut self) {
+    // No parameters means reset.
+    for index in 0..self.param_count.max(1) {
+      match self.params[index] {
+        0 => {
+          self.foreground = DEFAULT_FOREGROUND;
+          self.background = DEFAULT_BACKGROUND;
+          self.bold = false;
+        }
+        1 => self.bold = true,
+        22 => self.bold = false,
+        code @ 30..=37 => self.foreground = (code - 30) as u8,
+        39 => self.foreground = DEFAULT_FOREGROUND,
+        code @ 40..=47 => self.background = (code - 40) as u8,
+        49 => self.background = DEFAULT_BACKGROUND,
+        code @ 90..=97 => self.foreground = (code - 90) as u8 + BRIGHT,
+        code @ 100..=107 => self.background = (code - 100) as u8 + BRIGHT,
+        _ => {}
+      }
+    }
+  }
+
+  // Clears columns `start..end` of the cursor's row.
+  fn clear_line_range(&mut self, start: usize, end: usize) {
+    let end = end.min(self.columns);
+    if start >= end {
+      return;
+    }
+    let background = self.background_color();
+    let (x, y) = (start * self.cell_width(), self.row * self.cell_height());
+    let (width, height) = ((end - start) * self.cell_width(), self.cell_height());
+    self.framebuffer.fill_rect(x, y, width, height, background);
+  }
+
+  // Clears rows `start..end`.
+  fn clear_rows(&mut self, start: usize, end: usize) {
+    if start >= end {
+      return;
+    }
+    let background = self.background_color();
+    let (y, height) = (start * self.cell_height(), (end - start) * self.cell_height());
+    let width = self.framebuffer.width();
+    self.framebuffer.fill_rect(0, y, width, height, background);
+  }
+}